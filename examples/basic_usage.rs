@@ -39,16 +39,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = ValidatorConfig {
         clean_files: true,
         output_dir: Some(PathBuf::from("cleaned_output")),
+        ..Default::default()
     };
     
-    let (summary, _errors) = validate_directory_with_summary_serde(dir_path, &config)?;
+    let summary = validate_directory_with_summary_serde(dir_path, &config)?.summary;
     
     println!("Validation Summary:");
     println!("  Total files processed: {}", summary.total_files);
     println!("  Files with errors: {}", summary.files_with_errors);
     println!("  Total errors found: {}", summary.total_errors);
     
-    if summary.total_errors == 0 {
+    if summary.is_clean() {
         println!("✅ All files are valid!");
     } else {
         println!("❌ Found {} errors in {} files", summary.total_errors, summary.files_with_errors);
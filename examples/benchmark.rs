@@ -1,10 +1,9 @@
-use std::fs::File;
-use std::io::{self, BufWriter, Write};
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::PathBuf;
 use std::time::Instant;
 
+use ndjson_validator::corpus::{generate_test_corpus, CorpusConfig};
 use ndjson_validator::{validate_directory_with_summary_serde, validate_directory_with_summary_sonic, ValidatorConfig};
-use rand::{Rng, thread_rng};
 
 fn main() -> io::Result<()> {
     // Create a directory for test files
@@ -12,22 +11,33 @@ fn main() -> io::Result<()> {
     std::fs::create_dir_all(&test_dir)?;
     
     // Generate large test files
-    let num_files = 10;
-    let lines_per_file = 100_000; // 100K lines per file
-    let error_rate = 0.001; // 0.1% error rate
+    let corpus_config = CorpusConfig {
+        files: 10,
+        lines_per_file: 100_000, // 100K lines per file
+        error_rate: 0.001, // 0.1% error rate
+        seed: 42,
+    };
     
-    println!("Generating {} test files with {} lines each...", num_files, lines_per_file);
-    generate_test_files(&test_dir, num_files, lines_per_file, error_rate)?;
+    println!(
+        "Generating {} test files with {} lines each...",
+        corpus_config.files, corpus_config.lines_per_file
+    );
+    generate_test_corpus(&test_dir, &corpus_config)?;
 
     println!("\nRunning benchmark with serde_json...");
+    // Rayon spawns its thread pool lazily on first use. Without warming it up here, the serde_json
+    // run would also pay for starting the threads and look slower than sonic-rs for no good reason.
     let serde_config = ValidatorConfig {
         clean_files: false,
         output_dir: None,
-    };
+        ..Default::default()
+    }
+    .with_prewarm_threadpool(true);
     
     let start = Instant::now();
-    let (serde_summary, _) = validate_directory_with_summary_serde(&test_dir, &serde_config)
-        .expect("Failed to validate directory with serde_json");
+    let serde_summary = validate_directory_with_summary_serde(&test_dir, &serde_config)
+        .expect("Failed to validate directory with serde_json")
+        .summary;
     let serde_duration = start.elapsed();
     
     println!("Serde_json processing results:");
@@ -40,11 +50,13 @@ fn main() -> io::Result<()> {
     let sonic_config = ValidatorConfig {
         clean_files: false,
         output_dir: None,
+        ..Default::default()
     };
     
     let start = Instant::now();
-    let (sonic_summary, _) = validate_directory_with_summary_sonic(&test_dir, &sonic_config)
-        .expect("Failed to validate directory with sonic-rs");
+    let sonic_summary = validate_directory_with_summary_sonic(&test_dir, &sonic_config)
+        .expect("Failed to validate directory with sonic-rs")
+        .summary;
     let sonic_duration = start.elapsed();
     
     println!("Sonic-rs processing results:");
@@ -78,78 +90,3 @@ fn main() -> io::Result<()> {
     
     Ok(())
 }
-
-fn generate_test_files(
-    dir: &Path,
-    num_files: usize,
-    lines_per_file: usize,
-    error_rate: f64,
-) -> io::Result<()> {
-    let mut rng = thread_rng();
-    
-    for i in 0..num_files {
-        let file_path = dir.join(format!("test_file_{}.ndjson", i));
-        let file = File::create(&file_path)?;
-        let mut writer = BufWriter::new(file);
-        
-        for _ in 0..lines_per_file {
-            let has_error = rng.gen::<f64>() < error_rate;
-            
-            if has_error {
-                // Generate an invalid JSON line
-                let error_type = rng.gen_range(0..5);
-                let long_payload = "x".repeat(3000); // Adjusted for ~20000 chars total
-
-                match error_type {
-                    0 => { // Syntax error: missing colon after level4_long_key_name_jkl
-                        let s = format!(
-                            r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ "level4_long_key_name_jkl"  "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": "{}" }} }} }} }} }} }} }}"#,
-                            long_payload
-                        );
-                        writeln!(writer, "{}", s)?;
-                    }
-                    1 => { // Unclosed brace (missing final '}')
-                        let s = format!(
-                            r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ "level4_long_key_name_jkl": {{ "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": "{}" }} }} }} }} }} }}"#,
-                            long_payload
-                        );
-                        writeln!(writer, "{}", s)?;
-                    }
-                    2 => { // Missing quotes for a key (level4_long_key_name_jkl)
-                        let s = format!(
-                            r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ level4_long_key_name_jkl: {{ "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": "{}" }} }} }} }} }} }} }} }}"#,
-                            long_payload
-                        );
-                        writeln!(writer, "{}", s)?;
-                    }
-                    3 => { // Invalid value (unquoted literal)
-                        let s = format!(
-                            r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ "level4_long_key_name_jkl": {{ "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": invalid_value_{} }} }} }} }} }} }} }} }}"#,
-                            long_payload
-                        );
-                        writeln!(writer, "{}", s)?;
-                    }
-                    _ => { // Invalid escape sequence
-                        let s = format!(
-                            r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ "level4_long_key_name_jkl": {{ "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": "bad\\escape_{}" }} }} }} }} }} }} }} }}"#,
-                            long_payload
-                        );
-                        writeln!(writer, "{}", s)?;
-                    }
-                }
-            } else {
-                // Generate a valid JSON line
-                let long_payload = "v".repeat(3000); // Adjusted for ~20000 chars total
-                let s = format!(
-                    r#"{{ "level1_long_key_name_abc": {{ "level2_long_key_name_def": {{ "level3_long_key_name_ghi": {{ "level4_long_key_name_jkl": {{ "level5_long_key_name_mno": {{ "level6_long_key_name_pqr": {{ "level7_long_key_name_stu": {{ "level8_long_key_name_vwx": "{}" }} }} }} }} }} }} }} }}"#,
-                    long_payload
-                );
-                writeln!(writer, "{}", s)?;
-            }
-        }
-        
-        writer.flush()?;
-    }
-    
-    Ok(())
-}
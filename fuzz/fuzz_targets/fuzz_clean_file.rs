@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ndjson_validator::{clean_content_serde, ValidatorConfig};
+
+// Each input is a list of strings and whether to corrupt that line. Every string is written as a
+// JSON string literal, and a corrupted line loses its closing quote, so it can never parse.
+fuzz_target!(|input: Vec<(String, bool)>| {
+    let lines: Vec<(String, bool)> = input
+        .into_iter()
+        .map(|(value, corrupt)| {
+            let mut line = serde_json::to_string(&value).unwrap();
+            if corrupt {
+                line.pop();
+            }
+            (line, corrupt)
+        })
+        .collect();
+    let content: String = lines.iter().map(|(line, _)| format!("{}\n", line)).collect();
+
+    let cleaned = clean_content_serde(&content, &ValidatorConfig::default()).unwrap();
+
+    // The cleaned lines are a subset of the input lines, in the same relative order
+    let mut input_lines = lines.iter().map(|(line, _)| line.as_str());
+    for line in cleaned.lines() {
+        assert!(input_lines.any(|input_line| input_line == line), "line {:?} is not in the input", line);
+        assert!(serde_json::from_str::<serde_json::Value>(line).is_ok(), "line {:?} is not valid JSON", line);
+    }
+
+    // Exactly the uncorrupted lines are kept
+    let expected: Vec<&str> = lines.iter().filter(|(_, corrupt)| !corrupt).map(|(line, _)| line.as_str()).collect();
+    assert_eq!(cleaned.lines().collect::<Vec<_>>(), expected);
+});
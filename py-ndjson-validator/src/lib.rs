@@ -27,10 +27,10 @@ fn clean_ndjson_rust_serde(files: Vec<String>, output_dir: &str) -> PyResult<(Ve
     let output_dir_path = PathBuf::from(output_dir);
 
     // Create validator config
-    let config = ValidatorConfig {
-        clean_files: true,
-        output_dir: Some(output_dir_path.clone()),
-    };
+    let config = ValidatorConfig::builder()
+        .clean_files(true)
+        .output_dir(output_dir_path.clone())
+        .build();
 
     // Run validation and cleaning
     let errors = match validate_files_serde(&file_paths, &config) {
@@ -73,10 +73,10 @@ fn clean_ndjson_rust_sonic(files: Vec<String>, output_dir: &str) -> PyResult<(Ve
     let output_dir_path = PathBuf::from(output_dir);
 
     // Create validator config
-    let config = ValidatorConfig {
-        clean_files: true,
-        output_dir: Some(output_dir_path.clone()),
-    };
+    let config = ValidatorConfig::builder()
+        .clean_files(true)
+        .output_dir(output_dir_path.clone())
+        .build();
 
     // Run validation and cleaning using sonic-rs
     let errors = match validate_files_sonic(&file_paths, &config) {
@@ -115,7 +115,7 @@ fn clean_ndjson_rust_sonic(files: Vec<String>, output_dir: &str) -> PyResult<(Ve
 /// import the module.
 #[pymodule]
 #[pyo3(name = "py_ndjson_validator")]
-fn py_ndjson_validator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+pub fn py_ndjson_validator(m: &Bound<'_, PyModule>) -> PyResult<()> {
     let _ = m.add_function(wrap_pyfunction!(clean_ndjson_rust_serde, m)?);
     let _ = m.add_function(wrap_pyfunction!(clean_ndjson_rust_sonic, m)?);
     m.add_class::<ErrorEntry>()?;
@@ -0,0 +1,28 @@
+"""Tests for the Python bindings. Build them first with `maturin develop`, then run `pytest tests`."""
+
+from pathlib import Path
+
+import pytest
+
+from py_ndjson_validator import clean_ndjson_serde, clean_ndjson_sonic
+
+FIXTURE = Path(__file__).parent.parent / "examples" / "invalid1.ndjson"
+
+
+@pytest.mark.parametrize("clean", [clean_ndjson_serde, clean_ndjson_sonic])
+def test_clean_removes_invalid_lines(clean, tmp_path):
+    cleaned_files, errors = clean([FIXTURE], tmp_path)
+
+    assert len(errors) == 1
+    assert errors[0].line_number == 1
+    assert errors[0].file_path == str(FIXTURE)
+
+    assert cleaned_files == [tmp_path / "invalid1.ndjson"]
+    assert cleaned_files[0].read_text() == (
+        '{"name": "Bob", "age": 25}\n{"name": "Charlie", "age": 35}\n'
+    )
+
+
+def test_clean_missing_file_raises(tmp_path):
+    with pytest.raises(ValueError):
+        clean_ndjson_serde([tmp_path / "missing.ndjson"], tmp_path / "out")
@@ -0,0 +1,46 @@
+//! Calls the bindings through the Python interpreter to catch ABI breaks that Rust-only tests miss.
+//!
+//! Run with `cargo test`; the `extension-module` feature must be off so libpython is linked.
+
+use std::fs;
+use std::path::Path;
+
+use pyo3::prelude::*;
+use pyo3::wrap_pymodule;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/invalid1.ndjson");
+
+fn call_clean(function: &str, output_dir: &Path) {
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(py_ndjson_validator::py_ndjson_validator)(py);
+        let (cleaned_files, errors): (Vec<String>, Vec<Bound<'_, PyAny>>) = module
+            .getattr(py, function)
+            .unwrap()
+            .call1(py, (vec![FIXTURE], output_dir.to_str().unwrap()))
+            .unwrap()
+            .extract(py)
+            .unwrap();
+
+        assert_eq!(errors.len(), 1);
+        let line_number: usize = errors[0].getattr("line_number").unwrap().extract().unwrap();
+        assert_eq!(line_number, 1);
+
+        assert_eq!(cleaned_files.len(), 1);
+        let content = fs::read_to_string(&cleaned_files[0]).unwrap();
+        assert_eq!(content, "{\"name\": \"Bob\", \"age\": 25}\n{\"name\": \"Charlie\", \"age\": 35}\n");
+    });
+}
+
+#[test]
+fn test_clean_ndjson_rust_serde() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    call_clean("clean_ndjson_rust_serde", temp_dir.path());
+}
+
+#[test]
+fn test_clean_ndjson_rust_sonic() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    call_clean("clean_ndjson_rust_sonic", temp_dir.path());
+}
@@ -0,0 +1,620 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+
+use crate::config::ValidatorConfig;
+use crate::error::{Result, ValidationError, ValidationWarning};
+use crate::validator::{check_line, is_skipped, line_error, Backend, Lines, ParseError};
+
+/// Counts how often each top-level field appears across the valid JSON object lines of a file.
+///
+/// Invalid lines and lines that are not objects are ignored. This is a quick way to get a feel
+/// for the schema of an undocumented dataset.
+pub fn field_frequency_analysis(file_path: &Path) -> Result<HashMap<String, usize>> {
+    let file = File::open(file_path)?;
+    let mut frequencies = HashMap::new();
+
+    for line in Lines::new(BufReader::new(file), file_path) {
+        let line = line?;
+
+        if let Ok(Value::Object(object)) = serde_json::from_str::<Value>(&line.content) {
+            for key in object.keys() {
+                *frequencies.entry(key.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    Ok(frequencies)
+}
+
+/// Maximum number of example values kept per field by `infer_schema`
+const MAX_EXAMPLE_VALUES: usize = 3;
+
+/// Type of a JSON value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    /// Returns the type of `value`
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonType::Null,
+            Value::Bool(_) => JsonType::Bool,
+            Value::Number(_) => JsonType::Number,
+            Value::String(_) => JsonType::String,
+            Value::Array(_) => JsonType::Array,
+            Value::Object(_) => JsonType::Object,
+        }
+    }
+
+    /// Name of the type as used in JSON Schema, e.g. `"boolean"`
+    pub fn name(self) -> &'static str {
+        match self {
+            JsonType::Null => "null",
+            JsonType::Bool => "boolean",
+            JsonType::Number => "number",
+            JsonType::String => "string",
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+        }
+    }
+}
+
+/// What was observed about a single top-level field by `infer_schema`
+#[derive(Debug, Clone, Default)]
+pub struct FieldStats {
+    /// Every type the field had in the sampled lines
+    pub observed_types: HashSet<JsonType>,
+    /// Whether the field appeared in every sampled object line
+    pub always_present: bool,
+    /// The first few distinct values seen for the field
+    pub example_values: Vec<Value>,
+}
+
+/// Schema inferred from a sample of lines by `infer_schema`
+#[derive(Debug, Clone, Default)]
+pub struct InferredSchema {
+    /// Number of valid JSON object lines the schema was inferred from
+    pub objects_sampled: usize,
+    pub fields: HashMap<String, FieldStats>,
+}
+
+/// Infers the top-level fields of a file, their types and whether they are always present from
+/// its first `sample_lines` lines.
+///
+/// As with `field_frequency_analysis`, invalid lines and lines that are not objects are ignored,
+/// but they do count towards `sample_lines`.
+pub fn infer_schema(path: &Path, sample_lines: usize) -> Result<InferredSchema> {
+    let file = File::open(path)?;
+    let mut schema = InferredSchema::default();
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    for line in Lines::new(BufReader::new(file), path).take(sample_lines) {
+        let line = line?;
+
+        let Ok(Value::Object(object)) = serde_json::from_str::<Value>(&line.content) else {
+            continue;
+        };
+        schema.objects_sampled += 1;
+
+        for (key, value) in object {
+            *occurrences.entry(key.clone()).or_insert(0) += 1;
+
+            let stats = schema.fields.entry(key).or_default();
+            stats.observed_types.insert(JsonType::of(&value));
+            if stats.example_values.len() < MAX_EXAMPLE_VALUES && !stats.example_values.contains(&value) {
+                stats.example_values.push(value);
+            }
+        }
+    }
+
+    for (key, stats) in schema.fields.iter_mut() {
+        stats.always_present = occurrences[key] == schema.objects_sampled;
+    }
+
+    Ok(schema)
+}
+
+/// Which type of JSON value the valid lines of a file hold, found by `check_type_consistency`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeConsistencyReport {
+    /// The most common type, or `None` if the file has no valid lines. Ties go to the type seen first.
+    pub dominant_type: Option<JsonType>,
+    /// Line number and type of every valid line whose type is not the dominant one
+    pub inconsistent_lines: Vec<(usize, JsonType)>,
+}
+
+impl TypeConsistencyReport {
+    /// A warning for each inconsistent line, reported against `file_path`
+    pub fn warnings(&self, file_path: &Path) -> Vec<ValidationWarning> {
+        let Some(dominant_type) = self.dominant_type else {
+            return Vec::new();
+        };
+        self.inconsistent_lines
+            .iter()
+            .map(|(line_number, json_type)| ValidationWarning {
+                file_path: file_path.to_path_buf(),
+                line_number: Some(*line_number),
+                message: format!(
+                    "line {} is {:?}, but most lines are {:?}",
+                    line_number, json_type, dominant_type
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Checks that every valid line of a file holds the same type of JSON value, e.g. that an export
+/// of objects doesn't contain a stray array or string. Invalid and blank lines are ignored.
+pub fn check_type_consistency(path: &Path) -> Result<TypeConsistencyReport> {
+    let file = File::open(path)?;
+    let mut line_types = Vec::new();
+    let mut counts: HashMap<JsonType, usize> = HashMap::new();
+
+    for line in Lines::new(BufReader::new(file), path) {
+        let line = line?;
+        if let Some(json_type) = line_type(&line.content) {
+            *counts.entry(json_type).or_insert(0) += 1;
+            line_types.push((line.number, json_type));
+        }
+    }
+
+    // `max_by_key` keeps the last maximum, so walk the types in reverse order of first appearance
+    let mut seen = HashSet::new();
+    let first_seen: Vec<JsonType> = line_types.iter().map(|&(_, t)| t).filter(|t| seen.insert(*t)).collect();
+    let dominant_type = first_seen.into_iter().rev().max_by_key(|t| counts[t]);
+
+    let inconsistent_lines = line_types
+        .into_iter()
+        .filter(|&(_, json_type)| Some(json_type) != dominant_type)
+        .collect();
+
+    Ok(TypeConsistencyReport {
+        dominant_type,
+        inconsistent_lines,
+    })
+}
+
+/// Summarizes the types found in the first `sample` valid lines of a file as JSON, e.g. to log the
+/// schema of an undocumented dataset:
+///
+/// ```json
+/// {"lines_sampled": 2, "paths": {"/id": {"number": 2}, "/tags/*": {"string": 3}}}
+/// ```
+///
+/// `paths` maps the JSON Pointer of every leaf value to how often each type was seen there. Array
+/// indices are replaced by `*`, so all elements of an array share a path. Empty arrays and objects
+/// count as leaves, and a line that is a bare scalar has the empty pointer `""`. Invalid and blank
+/// lines are skipped and don't count towards `sample`.
+pub fn type_summary(path: &Path, sample: usize) -> Result<Value> {
+    let file = File::open(path)?;
+    let mut lines_sampled = 0;
+    let mut paths = serde_json::Map::new();
+
+    for line in Lines::new(BufReader::new(file), path) {
+        if lines_sampled == sample {
+            break;
+        }
+        let line = line?;
+        let Ok(value) = serde_json::from_str::<Value>(&line.content) else {
+            continue;
+        };
+        lines_sampled += 1;
+        record_leaf_types(&value, &mut String::new(), &mut paths);
+    }
+
+    Ok(serde_json::json!({
+        "lines_sampled": lines_sampled,
+        "paths": paths,
+    }))
+}
+
+/// Counts the type of every leaf under `value`, whose JSON Pointer is `pointer`
+fn record_leaf_types(value: &Value, pointer: &mut String, paths: &mut serde_json::Map<String, Value>) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(object) => object
+            .iter()
+            .map(|(key, child)| (key.replace('~', "~0").replace('/', "~1"), child))
+            .collect(),
+        Value::Array(array) => array.iter().map(|child| ("*".to_string(), child)).collect(),
+        _ => Vec::new(),
+    };
+
+    if children.is_empty() {
+        let counts = paths.entry(pointer.clone()).or_insert_with(|| Value::Object(serde_json::Map::new()));
+        let count = &mut counts[JsonType::of(value).name()];
+        *count = Value::from(count.as_u64().unwrap_or(0) + 1);
+        return;
+    }
+
+    for (segment, child) in children {
+        let parent_len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&segment);
+        record_leaf_types(child, pointer, paths);
+        pointer.truncate(parent_len);
+    }
+}
+
+/// Number of line sizes kept by `line_size_stats` to estimate the 95th percentile
+const LINE_SIZE_SAMPLE_CAPACITY: usize = 10_000;
+
+/// Size in bytes of the valid lines of a file, found by `line_size_stats`. Line endings are not
+/// counted. All fields are zero if the file has no valid lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineSizeStats {
+    pub min_bytes: usize,
+    pub max_bytes: usize,
+    pub mean_bytes: f64,
+    /// Exact for files with up to 10,000 valid lines, estimated from a uniform sample beyond that
+    pub p95_bytes: usize,
+}
+
+/// Computes size statistics over the valid lines of a file in a single pass. Invalid and blank
+/// lines are ignored.
+pub fn line_size_stats(path: &Path) -> Result<LineSizeStats> {
+    let file = File::open(path)?;
+    // Fixed seed, so the same file always gives the same estimate
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut sample = Vec::new();
+    let mut valid_lines = 0usize;
+    let mut total_bytes = 0u64;
+    let (mut min_bytes, mut max_bytes) = (usize::MAX, 0);
+
+    for line in Lines::new(BufReader::new(file), path) {
+        let line = line?;
+        if serde_json::from_str::<serde::de::IgnoredAny>(&line.content).is_err() {
+            continue;
+        }
+
+        let size = line.content.len();
+        valid_lines += 1;
+        total_bytes += size as u64;
+        min_bytes = min_bytes.min(size);
+        max_bytes = max_bytes.max(size);
+
+        // Reservoir sampling keeps every line equally likely to be in the sample
+        if sample.len() < LINE_SIZE_SAMPLE_CAPACITY {
+            sample.push(size);
+        } else {
+            let slot = rng.gen_range(0..valid_lines);
+            if slot < LINE_SIZE_SAMPLE_CAPACITY {
+                sample[slot] = size;
+            }
+        }
+    }
+
+    if valid_lines == 0 {
+        return Ok(LineSizeStats {
+            min_bytes: 0,
+            max_bytes: 0,
+            mean_bytes: 0.0,
+            p95_bytes: 0,
+        });
+    }
+
+    sample.sort_unstable();
+    let p95_index = (sample.len() * 95).div_ceil(100) - 1;
+
+    Ok(LineSizeStats {
+        min_bytes,
+        max_bytes,
+        mean_bytes: total_bytes as f64 / valid_lines as f64,
+        p95_bytes: sample[p95_index],
+    })
+}
+
+/// A TypeScript-style type that the values of `validate_typed_shape` must have. Lighter than JSON
+/// Schema, but checked by the compiler when built in Rust.
+///
+/// Objects may have fields beyond the ones listed, as in TypeScript. A field that is present must
+/// have its type even if it is optional, so `null` is only accepted where the type is `Nullable`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonShape {
+    /// Field name to the field's type and whether the field is required
+    Object(HashMap<String, (JsonShape, bool)>),
+    Array(Box<JsonShape>),
+    String,
+    Number,
+    Boolean,
+    /// The inner type or `null`
+    Nullable(Box<JsonShape>),
+}
+
+impl JsonShape {
+    /// The type written the TypeScript way, e.g. `string[] | null`
+    pub fn name(&self) -> String {
+        match self {
+            JsonShape::Object(_) => "object".to_string(),
+            JsonShape::Array(element) => match element.as_ref() {
+                JsonShape::Nullable(_) => format!("({})[]", element.name()),
+                _ => format!("{}[]", element.name()),
+            },
+            JsonShape::String => "string".to_string(),
+            JsonShape::Number => "number".to_string(),
+            JsonShape::Boolean => "boolean".to_string(),
+            JsonShape::Nullable(inner) => format!("{} | null", inner.name()),
+        }
+    }
+
+    /// Describes the first place where `value`, found at JSON Pointer `pointer`, doesn't have
+    /// this shape
+    fn find_mismatch(&self, value: &Value, pointer: &mut String) -> Option<String> {
+        let matches = match (self, value) {
+            (JsonShape::Nullable(_), Value::Null) => return None,
+            (JsonShape::Nullable(inner), _) => return inner.find_mismatch(value, pointer),
+            (JsonShape::Object(fields), Value::Object(object)) => {
+                // Sorted, so the same line always gets the same error
+                let mut names: Vec<&String> = fields.keys().collect();
+                names.sort();
+                for name in names {
+                    let (shape, required) = &fields[name];
+                    let parent_len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+                    let mismatch = match object.get(name) {
+                        Some(child) => shape.find_mismatch(child, pointer),
+                        None if *required => Some(format!("missing required field {}", pointer)),
+                        None => None,
+                    };
+                    pointer.truncate(parent_len);
+                    if mismatch.is_some() {
+                        return mismatch;
+                    }
+                }
+                true
+            }
+            (JsonShape::Array(element), Value::Array(array)) => {
+                for (index, child) in array.iter().enumerate() {
+                    let parent_len = pointer.len();
+                    pointer.push_str(&format!("/{}", index));
+                    let mismatch = element.find_mismatch(child, pointer);
+                    pointer.truncate(parent_len);
+                    if mismatch.is_some() {
+                        return mismatch;
+                    }
+                }
+                true
+            }
+            (JsonShape::String, Value::String(_)) => true,
+            (JsonShape::Number, Value::Number(_)) => true,
+            (JsonShape::Boolean, Value::Bool(_)) => true,
+            _ => false,
+        };
+        if matches {
+            return None;
+        }
+
+        let location = if pointer.is_empty() { "the root".to_string() } else { pointer.clone() };
+        Some(format!("expected {} at {}, found {}", self.name(), location, JsonType::of(value).name()))
+    }
+}
+
+/// Validates a single ND-JSON file and checks that every valid line has `shape`.
+///
+/// Lines that are not valid JSON are reported as usual. Lines that are valid JSON but don't have
+/// the shape are reported with an error starting with `shape mismatch:`, which names the JSON
+/// Pointer of the first offending value, e.g. `shape mismatch: expected string at /user/name,
+/// found number`.
+pub fn validate_typed_shape(path: &Path, shape: &JsonShape) -> Result<Vec<ValidationError>> {
+    let file = File::open(path)?;
+    let config = ValidatorConfig::default();
+    let mut errors = Vec::new();
+
+    for line in Lines::new(BufReader::new(file), path) {
+        let line = line?;
+        if let Some(error) = check_line(&line.content, line.number, path, &config, Backend::Serde) {
+            errors.push(error);
+            continue;
+        }
+        if is_skipped(&line.content, line.number, &config) {
+            continue;
+        }
+
+        // Already checked by `check_line`
+        let Ok(value) = serde_json::from_str::<Value>(&line.content) else {
+            continue;
+        };
+        if let Some(mismatch) = shape.find_mismatch(&value, &mut String::new()) {
+            let error = ParseError::without_position(format!("shape mismatch: {}", mismatch));
+            errors.push(line_error(&line.content, line.number, path, &config, error));
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Type of the value on a line, or `None` if the line is blank or invalid. The type is read off
+/// the first character, so the value itself is never built.
+fn line_type(line: &str) -> Option<JsonType> {
+    serde_json::from_str::<serde::de::IgnoredAny>(line).ok()?;
+    match line.trim_start().as_bytes().first()? {
+        b'{' => Some(JsonType::Object),
+        b'[' => Some(JsonType::Array),
+        b'"' => Some(JsonType::String),
+        b't' | b'f' => Some(JsonType::Bool),
+        b'n' => Some(JsonType::Null),
+        _ => Some(JsonType::Number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_field_frequency_analysis() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        fs::write(
+            &file_path,
+            "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2}\n{\"id\": }\n[1, 2]\n\n{\"id\": 3, \"tags\": []}\n",
+        )
+        .unwrap();
+
+        let frequencies = field_frequency_analysis(&file_path).unwrap();
+        assert_eq!(frequencies.len(), 3);
+        assert_eq!(frequencies["id"], 3);
+        assert_eq!(frequencies["name"], 1);
+        assert_eq!(frequencies["tags"], 1);
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        fs::write(
+            &file_path,
+            "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": null}\n{\"id\": }\n{\"id\": 3}\n{\"id\": 4}\n{\"late\": true}\n",
+        )
+        .unwrap();
+
+        let schema = infer_schema(&file_path, 5).unwrap();
+        assert_eq!(schema.objects_sampled, 4);
+        assert_eq!(schema.fields.len(), 2);
+
+        let id = &schema.fields["id"];
+        assert!(id.always_present);
+        assert_eq!(id.observed_types, HashSet::from([JsonType::Number]));
+        assert_eq!(id.example_values, vec![Value::from(1), Value::from(2), Value::from(3)]);
+
+        let name = &schema.fields["name"];
+        assert!(!name.always_present);
+        assert_eq!(name.observed_types, HashSet::from([JsonType::String, JsonType::Null]));
+    }
+
+    #[test]
+    fn test_check_type_consistency() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        fs::write(
+            &file_path,
+            "[1]\n{\"id\": 1}\n\n{\"id\": }\n{\"id\": 2}\n \"text\"\n-1.5\nnull\n[2]\n{\"id\": 3}\n",
+        )
+        .unwrap();
+
+        let report = check_type_consistency(&file_path).unwrap();
+        assert_eq!(report.dominant_type, Some(JsonType::Object));
+        assert_eq!(
+            report.inconsistent_lines,
+            vec![(1, JsonType::Array), (6, JsonType::String), (7, JsonType::Number), (8, JsonType::Null), (9, JsonType::Array)]
+        );
+
+        let warnings = report.warnings(&file_path);
+        assert_eq!(warnings.len(), 5);
+        assert_eq!(warnings[0].message, "line 1 is Array, but most lines are Object");
+
+        // Ties go to the type seen first
+        fs::write(&file_path, "true\n1\n2\nfalse\n").unwrap();
+        assert_eq!(check_type_consistency(&file_path).unwrap().dominant_type, Some(JsonType::Bool));
+    }
+
+    #[test]
+    fn test_line_size_stats() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        let mut content: String = (1..=20).map(|i| format!("{}\n", "1".repeat(i))).collect();
+        content.push_str("{\"invalid\": }\n\n");
+        fs::write(&file_path, content).unwrap();
+
+        let stats = line_size_stats(&file_path).unwrap();
+        assert_eq!(stats.min_bytes, 1);
+        assert_eq!(stats.max_bytes, 20);
+        assert_eq!(stats.mean_bytes, 10.5);
+        assert_eq!(stats.p95_bytes, 19);
+
+        fs::write(&file_path, "{\"invalid\": }\n").unwrap();
+        assert_eq!(line_size_stats(&file_path).unwrap().max_bytes, 0);
+    }
+
+    #[test]
+    fn test_type_summary() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        fs::write(
+            &file_path,
+            "{\"id\": 1, \"tags\": [\"a\", \"b\"], \"user\": {\"name\": \"x\", \"a/b\": null}}\n\
+             {\"id\": }\n\
+             {\"id\": \"2\", \"tags\": [], \"user\": {}}\n\
+             42\n\
+             {\"id\": 3}\n",
+        )
+        .unwrap();
+
+        let summary = type_summary(&file_path, 3).unwrap();
+        assert_eq!(
+            summary,
+            serde_json::json!({
+                "lines_sampled": 3,
+                "paths": {
+                    "": {"number": 1},
+                    "/id": {"number": 1, "string": 1},
+                    "/tags": {"array": 1},
+                    "/tags/*": {"string": 2},
+                    "/user": {"object": 1},
+                    "/user/a~1b": {"null": 1},
+                    "/user/name": {"string": 1},
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_typed_shape() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("users.ndjson");
+        fs::write(
+            &path,
+            concat!(
+                "{\"id\": 1, \"name\": \"Ada\", \"tags\": [\"x\"], \"manager\": null, \"extra\": true}\n",
+                "{\"id\": 2, \"tags\": []}\n",
+                "{\"id\": \"3\", \"tags\": []}\n",
+                "{\"tags\": []}\n",
+                "{\"id\": 5, \"tags\": [\"x\", 5]}\n",
+                "{\"id\": 6, \"tags\": [], \"name\": null}\n",
+                "[1]\n",
+                "{\"id\": }\n",
+            ),
+        )
+        .unwrap();
+        let shape = JsonShape::Object(HashMap::from([
+            ("id".to_string(), (JsonShape::Number, true)),
+            ("name".to_string(), (JsonShape::String, false)),
+            ("tags".to_string(), (JsonShape::Array(Box::new(JsonShape::String)), true)),
+            ("manager".to_string(), (JsonShape::Nullable(Box::new(JsonShape::Number)), false)),
+        ]));
+
+        let errors = validate_typed_shape(&path, &shape).unwrap();
+        let messages: Vec<(usize, &str)> = errors.iter().map(|e| (e.line_number, e.error.as_str())).collect();
+        assert_eq!(messages[..5], [
+            (3, "shape mismatch: expected number at /id, found string"),
+            (4, "shape mismatch: missing required field /id"),
+            (5, "shape mismatch: expected string at /tags/1, found number"),
+            (6, "shape mismatch: expected string at /name, found null"),
+            (7, "shape mismatch: expected object at the root, found array"),
+        ]);
+        assert_eq!(errors.len(), 6);
+        assert!(!errors[5].error.starts_with("shape mismatch"));
+    }
+
+    #[test]
+    fn test_json_shape_name() {
+        let shape = JsonShape::Array(Box::new(JsonShape::Nullable(Box::new(JsonShape::Number))));
+        assert_eq!(shape.name(), "(number | null)[]");
+        assert_eq!(JsonShape::Nullable(Box::new(JsonShape::Array(Box::new(JsonShape::Boolean)))).name(), "boolean[] | null");
+    }
+}
@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+
+use tokio::sync::mpsc::Sender;
+
+use crate::config::ValidatorConfig;
+use crate::error::{NdJsonError, Result, ValidationError, ValidationSummary};
+use crate::validator::{for_each_error, Backend};
+
+/// Validates a file with serde_json and sends each error through `tx` as soon as it is found, so
+/// the full error list is never held in memory.
+///
+/// The file is read on Tokio's blocking thread pool. When the channel is full, reading waits for
+/// the receiver to catch up. Errors stop after `config.max_errors_per_file` or
+/// `config.max_total_errors`, whichever is lower, or with `config.fail_fast` after the first one.
+/// `clean_files` is ignored. Fails with a `BrokenPipe` IO error if the receiver is dropped before
+/// the whole file has been read.
+pub async fn validate_file_streaming_serde(
+    path: PathBuf,
+    config: ValidatorConfig,
+    tx: Sender<ValidationError>,
+) -> Result<ValidationSummary> {
+    let task = tokio::task::spawn_blocking(move || {
+        let file = File::open(&path)?;
+        let mut total_errors = 0;
+        // A single file is the whole batch, so the batch limit applies to it too
+        let error_limit = config.error_limit_per_file().into_iter().chain(config.max_total_errors).min();
+        let (_, truncated) = for_each_error(BufReader::new(file), &path, &config, Backend::Serde, error_limit, |error| {
+            tx.blocking_send(error).map_err(|_| {
+                NdJsonError::Io(io::Error::new(io::ErrorKind::BrokenPipe, "error receiver was dropped"))
+            })?;
+            total_errors += 1;
+            Ok(())
+        })?;
+
+        Ok(ValidationSummary {
+            total_files: 1,
+            files_with_errors: usize::from(total_errors > 0),
+            total_errors,
+            partial_scan: config.first_line_only || config.line_range.is_some(),
+            truncated,
+        })
+    });
+
+    task.await.map_err(|e| NdJsonError::Io(io::Error::other(e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_validate_file_streaming_serde() {
+        // A capacity of 1 makes the reader wait for every error to be received
+        let (tx, mut rx) = mpsc::channel(1);
+        let task = tokio::spawn(validate_file_streaming_serde(
+            PathBuf::from("tests/invalid2.ndjson"),
+            ValidatorConfig::default(),
+            tx,
+        ));
+
+        let mut received = Vec::new();
+        while let Some(error) = rx.recv().await {
+            received.push(error);
+        }
+
+        let summary = task.await.unwrap().unwrap();
+        assert!(!received.is_empty());
+        assert_eq!(summary.total_errors, received.len());
+        assert_eq!(summary.files_with_errors, 1);
+        assert!(received.windows(2).all(|pair| pair[0].line_number < pair[1].line_number));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_receiver() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+
+        let result = validate_file_streaming_serde(PathBuf::from("tests/invalid2.ndjson"), ValidatorConfig::default(), tx).await;
+        match result {
+            Err(NdJsonError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::BrokenPipe),
+            other => panic!("expected a BrokenPipe error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_total_errors() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let config = ValidatorConfig {
+            max_total_errors: Some(2),
+            ..Default::default()
+        };
+        let summary = validate_file_streaming_serde(PathBuf::from("tests/invalid2.ndjson"), config, tx).await.unwrap();
+
+        let mut received = Vec::new();
+        while let Some(error) = rx.recv().await {
+            received.push(error);
+        }
+        assert_eq!(received.len(), 2);
+        assert_eq!(summary.total_errors, 2);
+        assert!(summary.truncated);
+    }
+}
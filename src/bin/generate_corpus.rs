@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use ndjson_validator::corpus::{generate_test_corpus, CorpusConfig};
+
+/// Generate a reproducible corpus of ND-JSON test files
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Number of files to generate
+    #[arg(long, default_value_t = 10)]
+    files: usize,
+
+    /// Number of lines per file
+    #[arg(long, default_value_t = 1000)]
+    lines: usize,
+
+    /// Probability that a line is invalid JSON (between 0.0 and 1.0)
+    #[arg(long, default_value_t = 0.01)]
+    error_rate: f64,
+
+    /// Seed for the random number generator
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Directory to write the files to
+    #[arg(long, default_value = "corpus")]
+    output_dir: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let config = CorpusConfig {
+        files: args.files,
+        lines_per_file: args.lines,
+        error_rate: args.error_rate,
+        seed: args.seed,
+    };
+
+    let file_paths = generate_test_corpus(&args.output_dir, &config)
+        .with_context(|| format!("Failed to generate corpus in {}", args.output_dir.display()))?;
+
+    println!(
+        "Wrote {} files with {} lines each to {} (seed {})",
+        file_paths.len(),
+        args.lines,
+        args.output_dir.display(),
+        args.seed
+    );
+
+    Ok(())
+}
@@ -1,34 +1,144 @@
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
-use crate::error::{Result, ValidationError};
+use serde_json::Value;
+
+use crate::config::ValidatorConfig;
+use crate::error::{NdJsonError, Result, ValidationError};
+use crate::float_precision::normalize_numbers;
+use crate::validator::{is_skipped, validate_reader, Backend, Line, LineChecker, Lines};
 
 /// Writes a cleaned version of the file without the invalid JSON lines
-pub fn clean_file(input_path: &Path, output_path: &Path, errors: &[ValidationError]) -> Result<()> {
+///
+/// If `config.invalid_line_replacement` is set, invalid lines are replaced with it instead of
+/// being removed, so the output keeps the same number of lines as the input. If
+/// `config.sort_by_field` is set, the lines after the header are sorted by that field.
+pub fn clean_file(
+    input_path: &Path,
+    output_path: &Path,
+    errors: &[ValidationError],
+    config: &ValidatorConfig,
+) -> Result<()> {
     let input_file = File::open(input_path)?;
-    let reader = BufReader::new(input_file);
-    
-    let invalid_lines: HashSet<usize> = errors.iter()
-        .map(|e| e.line_number)
-        .collect();
-    
-    let mut lines_written = 0;
+    clean_reader(BufReader::new(input_file), output_path, errors, config)
+}
+
+/// Validates in-memory ND-JSON with serde_json and returns it without the invalid lines, cleaned
+/// the same way as `clean_file`
+pub fn clean_content_serde(content: &str, config: &ValidatorConfig) -> Result<String> {
+    check_full_scan(config)?;
+    let errors = validate_reader(content.as_bytes(), Path::new(CONTENT_LABEL), config, Backend::Serde)?;
+    let mut cleaned = Vec::new();
+    write_cleaned(content.as_bytes(), &mut cleaned, &errors, config)?;
     
+    // Only whole lines of the input are written, so this is still valid UTF-8
+    Ok(String::from_utf8(cleaned).expect("cleaned content is valid UTF-8"))
+}
+
+/// Lines of context around each change in `clean_file_diff`, the same as `diff -u`
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Returns a unified diff (`diff -u` format) from the file to its cleaned version, which removes
+/// the lines listed in `errors`. The diff can be reviewed before cleaning or applied with `patch`.
+///
+/// Only the removal of invalid lines is shown; other options of `clean_file`, like sorting or
+/// replacing invalid lines, are not. A file without errors gives an empty diff.
+pub fn clean_file_diff(input_path: &Path, errors: &[ValidationError]) -> Result<String> {
+    let content = fs::read_to_string(input_path)?;
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let removed: HashSet<usize> = errors.iter().map(|e| e.line_number - 1).filter(|&i| i < lines.len()).collect();
+    if removed.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut removed_sorted: Vec<usize> = removed.iter().copied().collect();
+    removed_sorted.sort_unstable();
+
+    // Ranges of lines covered by each hunk, merging hunks whose context would overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &index in &removed_sorted {
+        let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (index + DIFF_CONTEXT_LINES + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let path = input_path.display();
+    let mut diff = format!("--- {}\n+++ {}\n", path, path);
+    let mut removed_before = 0;
+    let mut next_removed = removed_sorted.iter().peekable();
+
+    for (start, end) in hunks {
+        while next_removed.next_if(|&&index| index < start).is_some() {
+            removed_before += 1;
+        }
+        let removed_in_hunk = (start..end).filter(|index| removed.contains(index)).count();
+        let new_start = start - removed_before;
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(start, end - start),
+            hunk_range(new_start, end - start - removed_in_hunk)
+        ));
+
+        for (index, line) in lines.iter().enumerate().take(end).skip(start) {
+            diff.push(if removed.contains(&index) { '-' } else { ' ' });
+            diff.push_str(line);
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    Ok(diff)
+}
+
+/// Formats the range of a hunk from its 0-based start line. Like `diff -u`, a length of 1 is left
+/// out, and an empty range starts at the line before it.
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+/// Synthetic file path used for in-memory content
+const CONTENT_LABEL: &str = "<content>";
+
+/// Cleaning keeps every line without an error, so it needs every line to have been validated
+pub(crate) fn check_full_scan(config: &ValidatorConfig) -> Result<()> {
+    if config.first_line_only {
+        return Err(NdJsonError::InvalidConfig(
+            "first_line_only only validates part of each file and cannot be combined with cleaning".to_string(),
+        ));
+    }
+    if config.line_range.is_some() {
+        return Err(NdJsonError::InvalidConfig(
+            "line_range only validates part of each file and cannot be combined with cleaning".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Writes a cleaned version of the ND-JSON read from `reader` to `output_path`
+pub(crate) fn clean_reader(
+    reader: impl BufRead,
+    output_path: &Path,
+    errors: &[ValidationError],
+    config: &ValidatorConfig,
+) -> Result<()> {
+    check_full_scan(config)?;
+
     // Create the output file. It will be empty initially or truncated if it exists.
     let output_file_handle = File::create(output_path)?;
     let mut writer = BufWriter::new(output_file_handle);
     
-    for (i, line_result) in reader.lines().enumerate() {
-        let line_number = i + 1;
-        let line = line_result?; // Propagates IO errors from reading lines
-        
-        if !invalid_lines.contains(&line_number) {
-            writeln!(writer, "{}", line)?; // Propagates IO errors from writing lines
-            lines_written += 1;
-        }
-    }
+    let lines_written = write_cleaned(reader, &mut writer, errors, config)?;
     
     writer.flush()?; // Ensure all buffered data is written to the underlying file.
     drop(writer); // Explicitly drop writer to close the file before potential deletion.
@@ -41,6 +151,102 @@ pub fn clean_file(input_path: &Path, output_path: &Path, errors: &[ValidationErr
     Ok(())
 }
 
+/// Writes the lines of `reader` that aren't listed in `errors` to `writer`, returning the number
+/// of lines written
+fn write_cleaned(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    errors: &[ValidationError],
+    config: &ValidatorConfig,
+) -> Result<usize> {
+    let invalid_lines: HashSet<usize> = errors.iter()
+        .map(|e| e.line_number)
+        .collect();
+    
+    let mut lines_written = 0;
+    
+    // Sorting needs every line up front, so those are held back until the input is exhausted
+    let mut lines_to_sort = Vec::new();
+    let mut checker = LineChecker::new(config);
+    
+    // The reader has no path of its own. Validation rejects non-UTF-8 input before it gets here
+    // unless the fallback is enabled, so the label hardly ever shows up in an error.
+    for line_result in Lines::new(reader, Path::new(CONTENT_LABEL)).with_latin1_fallback(config.encoding_fallback) {
+        let Line { number: line_number, content: line, .. } = line_result?; // Propagates IO errors from reading lines
+        let is_header = line_number <= config.skip_header_lines || checker.is_schema_header(&line, line_number, config);
+        
+        let output_line = if !invalid_lines.contains(&line_number) {
+            // The line was only accepted without its stray carriage returns, so it is written without them
+            let line = if config.normalize_line_endings && line.contains('\r') { line.replace('\r', "") } else { line };
+            if config.normalize_numbers && !is_header && !is_skipped(&line, line_number, config) {
+                normalize_numbers(&line)
+            } else {
+                line
+            }
+        } else if let Some(replacement) = &config.invalid_line_replacement {
+            replacement.clone()
+        } else {
+            continue;
+        };
+        
+        if config.sort_by_field.is_some() && !is_header {
+            lines_to_sort.push(output_line);
+        } else {
+            writeln!(writer, "{}", output_line)?; // Propagates IO errors from writing lines
+            lines_written += 1;
+        }
+    }
+    
+    if let Some(field) = &config.sort_by_field {
+        sort_lines_by_field(&mut lines_to_sort, field);
+        for line in &lines_to_sort {
+            writeln!(writer, "{}", line)?;
+        }
+        lines_written += lines_to_sort.len();
+    }
+    
+    Ok(lines_written)
+}
+
+/// Value of the sort field in a line. Numbers sort before strings, and lines where the field is
+/// missing or has another type sort last.
+enum SortKey {
+    Number(f64),
+    String(String),
+    Missing,
+}
+
+impl SortKey {
+    fn from_line(line: &str, field: &str) -> Self {
+        match serde_json::from_str::<Value>(line).ok().as_ref().and_then(|value| value.get(field)) {
+            Some(Value::Number(n)) => n.as_f64().map_or(SortKey::Missing, SortKey::Number),
+            Some(Value::String(s)) => SortKey::String(s.clone()),
+            _ => SortKey::Missing,
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::String(a), SortKey::String(b)) => a.cmp(b),
+            (SortKey::Number(_), _) | (SortKey::String(_), SortKey::Missing) => Ordering::Less,
+            (SortKey::Missing, SortKey::Missing) => Ordering::Equal,
+            _ => Ordering::Greater,
+        }
+    }
+}
+
+/// Sorts lines by the value of a top-level field. The sort is stable, so lines with equal keys
+/// keep their input order.
+fn sort_lines_by_field(lines: &mut Vec<String>, field: &str) {
+    let mut keyed: Vec<(SortKey, String)> = lines
+        .drain(..)
+        .map(|line| (SortKey::from_line(&line, field), line))
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    lines.extend(keyed.into_iter().map(|(_, line)| line));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,17 +273,23 @@ mod tests {
                 line_number: 2,
                 line_content: "line2".to_string(),
                 error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
             },
             ValidationError {
                 file_path: input_path.to_path_buf(),
                 line_number: 4,
                 line_content: "line4".to_string(),
                 error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
             },
         ];
         
         // Clean the file
-        clean_file(input_path, output_path, &errors).unwrap();
+        clean_file(input_path, output_path, &errors, &ValidatorConfig::default()).unwrap();
         
         // Read the output file
         let content = fs::read_to_string(output_path).unwrap();
@@ -103,17 +315,181 @@ mod tests {
                 line_number: 1,
                 line_content: "corrupt1".to_string(),
                 error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
             },
             ValidationError {
                 file_path: input_path.to_path_buf(),
                 line_number: 2,
                 line_content: "corrupt2".to_string(),
                 error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
             },
         ];
         
-        clean_file(input_path, &output_path, &errors).unwrap();
+        clean_file(input_path, &output_path, &errors, &ValidatorConfig::default()).unwrap();
         
         assert!(!output_path.exists(), "Output file should not exist when all lines are invalid");
     }
+
+    #[test]
+    fn test_clean_file_replaces_invalid_lines() {
+        let input_file = NamedTempFile::new().unwrap();
+        let input_path = input_file.path();
+        fs::write(input_path, "{\"a\": 1}\ncorrupt\n{\"a\": 3}\ncorrupt\n").unwrap();
+        
+        let output_file = NamedTempFile::new().unwrap();
+        let output_path = output_file.path();
+        
+        let errors: Vec<ValidationError> = [2, 4]
+            .into_iter()
+            .map(|line_number| ValidationError {
+                file_path: input_path.to_path_buf(),
+                line_number,
+                line_content: "corrupt".to_string(),
+                error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
+            })
+            .collect();
+        let config = ValidatorConfig {
+            invalid_line_replacement: Some("{}".to_string()),
+            ..Default::default()
+        };
+        
+        clean_file(input_path, output_path, &errors, &config).unwrap();
+        
+        // The line count is preserved and the placeholders sit where the invalid lines were
+        let content = fs::read_to_string(output_path).unwrap();
+        assert_eq!(content, "{\"a\": 1}\n{}\n{\"a\": 3}\n{}\n");
+    }
+
+    #[test]
+    fn test_clean_content() {
+        let content = "{\"a\": 1}\n{\"a\": }\n\n[1, 2]\n\"unterminated\n";
+        
+        let cleaned = clean_content_serde(content, &ValidatorConfig::default()).unwrap();
+        assert_eq!(cleaned, "{\"a\": 1}\n\n[1, 2]\n");
+    }
+
+    #[test]
+    fn test_clean_file_diff() {
+        let temp_dir = tempdir().unwrap();
+        let input_path = temp_dir.path().join("input.ndjson");
+        let content: String = (1..=12).map(|i| format!("{{\"n\": {}}}\n", i)).collect();
+        fs::write(&input_path, format!("{}bad", content.replacen("{\"n\": 2}", "bad2", 1))).unwrap();
+
+        let errors: Vec<ValidationError> = [2, 13]
+            .into_iter()
+            .map(|line_number| ValidationError {
+                file_path: input_path.clone(),
+                line_number,
+                line_content: String::new(),
+                error: "test error".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
+            })
+            .collect();
+
+        let path = input_path.display();
+        let expected = format!(
+            "--- {path}\n+++ {path}\n\
+             @@ -1,5 +1,4 @@\n {{\"n\": 1}}\n-bad2\n {{\"n\": 3}}\n {{\"n\": 4}}\n {{\"n\": 5}}\n\
+             @@ -10,4 +9,3 @@\n {{\"n\": 10}}\n {{\"n\": 11}}\n {{\"n\": 12}}\n-bad\n\\ No newline at end of file\n"
+        );
+        assert_eq!(clean_file_diff(&input_path, &errors).unwrap(), expected);
+        assert_eq!(clean_file_diff(&input_path, &[]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_clean_file_sorts_by_field() {
+        let input_file = NamedTempFile::new().unwrap();
+        let input_path = input_file.path();
+        fs::write(
+            input_path,
+            "header\n{\"schema\": {}}\n{\"id\": 10}\n{\"id\": \"b\"}\ncorrupt\n{\"other\": 1}\n{\"id\": 2}\n{\"id\": \"a\"}\n{\"id\": 2.5}\n",
+        )
+        .unwrap();
+        
+        let output_file = NamedTempFile::new().unwrap();
+        let output_path = output_file.path();
+        
+        let errors = vec![ValidationError {
+            file_path: input_path.to_path_buf(),
+            line_number: 5,
+            line_content: "corrupt".to_string(),
+            error: "test error".to_string(),
+            line_hash: None,
+            column_number: None,
+            sonic_error_offset: None,
+        }];
+        let config = ValidatorConfig {
+            skip_header_lines: 1,
+            skip_schema_header: true,
+            sort_by_field: Some("id".to_string()),
+            ..Default::default()
+        };
+        
+        clean_file(input_path, output_path, &errors, &config).unwrap();
+        
+        // The headers stay first, numbers sort numerically before strings, and lines without the field go last
+        let content = fs::read_to_string(output_path).unwrap();
+        assert_eq!(
+            content,
+            "header\n{\"schema\": {}}\n{\"id\": 2}\n{\"id\": 2.5}\n{\"id\": 10}\n{\"id\": \"a\"}\n{\"id\": \"b\"}\n{\"other\": 1}\n"
+        );
+    }
+
+    #[test]
+    fn test_clean_content_normalizes_numbers() {
+        let config = ValidatorConfig {
+            normalize_numbers: true,
+            allow_comment_lines: true,
+            invalid_line_replacement: Some("{\"v\": 2.0}".to_string()),
+            ..Default::default()
+        };
+        let content = "// version 1.0\n{\"z\": 1.0, \"a\": [1e3, 2.50]}\n{\"v\": }\n";
+        let cleaned = clean_content_serde(content, &config).unwrap();
+        // Comments and replacement lines are written as they are
+        assert_eq!(cleaned, "// version 1.0\n{\"z\": 1, \"a\": [1000, 2.5]}\n{\"v\": 2.0}\n");
+    }
+
+    #[test]
+    fn test_clean_content_normalizes_line_endings() {
+        let config = ValidatorConfig {
+            normalize_line_endings: true,
+            ..Default::default()
+        };
+        let cleaned = clean_content_serde("{\"name\": \"a\r\"}\r\n{\"name\": }\n", &config).unwrap();
+        assert_eq!(cleaned, "{\"name\": \"a\"}\n");
+        assert!(validate_reader(cleaned.as_bytes(), Path::new(CONTENT_LABEL), &ValidatorConfig::default(), Backend::Serde).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clean_rejects_line_range() {
+        let config = ValidatorConfig {
+            line_range: Some(2..3),
+            ..Default::default()
+        };
+        let result = clean_content_serde("{\"id\": }\n{\"id\": 2}\n", &config);
+        assert!(matches!(result, Err(NdJsonError::InvalidConfig(_))));
+
+        let config = ValidatorConfig {
+            first_line_only: true,
+            ..Default::default()
+        };
+        let result = clean_content_serde("{\"id\": 1}\n{\"id\": }\n", &config);
+        assert!(matches!(result, Err(NdJsonError::InvalidConfig(_))));
+
+        let temp_dir = tempdir().unwrap();
+        let output_path = temp_dir.path().join("out.ndjson");
+        let result = clean_reader("{\"id\": 1}\n".as_bytes(), &output_path, &[], &config);
+        assert!(matches!(result, Err(NdJsonError::InvalidConfig(_))));
+        assert!(!output_path.exists());
+    }
 }
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use ndjson_validator::OutputFormat;
 use std::path::PathBuf;
 
 /// Tool for validating and cleaning ND-JSON files
@@ -24,6 +25,14 @@ pub enum Commands {
         /// Directory to output cleaned files to
         #[arg(short, long, required_if_eq("clean", "true"))]
         output_dir: Option<PathBuf>,
+        
+        /// Also show size statistics of the valid lines, in human output only
+        #[arg(long)]
+        stats: bool,
+
+        /// Format of the report: human (or text), json, csv, ndjson or html
+        #[arg(short = 'f', long = "format", visible_alias = "output-format", default_value = "human")]
+        output_format: OutputFormat,
     },
     
     /// Validate multiple ND-JSON files
@@ -39,6 +48,18 @@ pub enum Commands {
         /// Directory to output cleaned files to
         #[arg(short, long, required_if_eq("clean", "true"))]
         output_dir: Option<PathBuf>,
+
+        /// Format of the report: human (or text), json, csv, ndjson or html
+        #[arg(short = 'f', long = "format", visible_alias = "output-format", default_value = "human")]
+        output_format: OutputFormat,
+
+        /// Write the report to this file instead of standard output
+        #[arg(long)]
+        report_file: Option<PathBuf>,
+
+        /// Only validate the first non-empty line of each file
+        #[arg(long, conflicts_with = "clean")]
+        first_line_only: bool,
     },
     
     /// Validate all ND-JSON files in a directory
@@ -54,5 +75,103 @@ pub enum Commands {
         /// Directory to output cleaned files to
         #[arg(short, long, required_if_eq("clean", "true"))]
         output_dir: Option<PathBuf>,
+
+        /// Format of the report: human (or text), json, csv, ndjson or html
+        #[arg(short = 'f', long = "format", visible_alias = "output-format", default_value = "human")]
+        output_format: OutputFormat,
+
+        /// Write the report to this file instead of standard output
+        #[arg(long)]
+        report_file: Option<PathBuf>,
+
+        /// Only validate the first non-empty line of each file
+        #[arg(long, conflicts_with = "clean")]
+        first_line_only: bool,
+    },
+
+    /// Validate ND-JSON read from standard input
+    ValidatePipe {
+        /// Clean the input by removing invalid JSON lines
+        #[arg(short, long)]
+        clean: bool,
+
+        /// Directory to output the cleaned input to (defaults to standard output)
+        #[arg(short, long)]
+        output_dir: Option<PathBuf>,
+    },
+
+    /// Show how often each top-level field occurs in an ND-JSON file
+    Analyze {
+        /// Path to the ND-JSON file
+        #[arg(required = true)]
+        file_path: PathBuf,
+    },
+
+    /// Show size, line counts, fields, newline style and encoding of an ND-JSON file
+    Inspect {
+        /// Path to the ND-JSON file
+        #[arg(required = true)]
+        file_path: PathBuf,
+
+        /// Format of the output: human (or text) or json
+        #[arg(short = 'f', long = "format", visible_alias = "output-format", default_value = "human")]
+        output_format: OutputFormat,
+    },
+
+    /// Time serde_json and sonic-rs on an ND-JSON file and report where they disagree
+    Benchmark {
+        /// Path to the ND-JSON file
+        #[arg(required = true)]
+        file_path: PathBuf,
+    },
+
+    /// Generate a reproducible set of ND-JSON test files with a mix of valid and invalid lines
+    GenerateFixtures {
+        /// Directory to write the files to
+        #[arg(short, long, required = true)]
+        output_dir: PathBuf,
+
+        /// Number of files to generate
+        #[arg(long, default_value_t = 10)]
+        files: usize,
+
+        /// Number of lines per file
+        #[arg(long, default_value_t = 1000)]
+        lines: usize,
+
+        /// Probability that a line is invalid JSON (between 0.0 and 1.0)
+        #[arg(long, default_value_t = 0.01)]
+        error_rate: f64,
+
+        /// Seed for the random number generator
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+    },
+
+    /// Convert a CSV file to ND-JSON
+    ConvertFromCsv {
+        /// Path to the CSV file
+        #[arg(required = true)]
+        input: PathBuf,
+
+        /// Path to write the ND-JSON file to
+        #[arg(required = true)]
+        output: PathBuf,
+
+        /// Field delimiter (a single ASCII character)
+        #[arg(short, long, default_value_t = ',')]
+        delimiter: char,
+
+        /// Treat the first row as data rather than key names, writing every row as a JSON array
+        #[arg(long)]
+        no_header: bool,
+    },
+
+    /// Serve validation over HTTP (`POST /validate` and `POST /clean`)
+    #[cfg(feature = "http-server")]
+    Server {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
     },
 }
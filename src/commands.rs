@@ -1,71 +1,43 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use ndjson_validator::convert::csv_to_ndjson;
+use ndjson_validator::corpus::{generate_test_corpus, CorpusConfig};
 use ndjson_validator::{
-    validate_directory_with_summary_serde, validate_file_serde, validate_files_with_summary_serde, 
-    ValidationError, ValidationSummary, ValidatorConfig
+    compare_backends, field_frequency_analysis, line_size_stats, validate_directory_with_summary_serde, validate_file_serde, validate_files_with_summary_serde, 
+    validate_file_sonic, validate_stdin_serde, OutputFormat, Report, ValidatorConfig
 };
 
-/// Prints a summary of validation results
-pub fn print_summary(summary: &ValidationSummary, duration: std::time::Duration) {
-    println!("Validation Summary:");
-    println!("  Total files processed: {}", summary.total_files);
-    println!("  Files with errors: {}", summary.files_with_errors);
-    println!("  Total errors found: {}", summary.total_errors);
-    println!("  Time taken: {:.2?}", duration);
-    
-    if summary.total_errors == 0 {
-        println!("✅ All files are valid!");
-    } else {
-        println!("❌ Found {} errors in {} files", summary.total_errors, summary.files_with_errors);
-    }
-}
+use crate::output::{
+    print_cleaning_info, print_errors, truncate_chars, write_errors, DEFAULT_MAX_LINE_CONTENT_CHARS,
+};
 
-/// Prints detailed error information
-pub fn print_errors(errors: &[ValidationError]) {
-    if errors.is_empty() {
-        return;
-    }
-    
-    let max_errors_to_display = 10;
-    let display_count = std::cmp::min(errors.len(), max_errors_to_display);
-    
-    println!("\nError Details (showing first {}/{}):", display_count, errors.len());
-    
-    for (i, error) in errors.iter().take(display_count).enumerate() {
-        println!("{}. File: {}", i + 1, error.file_path.display());
-        println!("   Line {}: {}", error.line_number, error.line_content);
-        println!("   Error: {}", error.error);
-        println!();
-    }
-    
-    if errors.len() > max_errors_to_display {
-        println!("... and {} more errors", errors.len() - max_errors_to_display);
-    }
-}
+pub fn handle_validate_file(
+    file_path: &Path,
+    clean: bool,
+    output_dir: &Option<PathBuf>,
+    stats: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let config = ValidatorConfig {
+        clean_files: clean,
+        output_dir: output_dir.clone(),
+        ..Default::default()
+    };
 
-/// Prints information about the cleaning process
-pub fn print_cleaning_info(input_path: &Path, output_dir: &Path, error_count: usize) {
-    let file_name = input_path.file_name().unwrap_or_default();
-    let output_path = output_dir.join(file_name);
-    
-    if error_count == 0 {
-        println!("No errors to clean up.");
-    } else {
-        println!("Cleaned file written to: {}", output_path.display());
-        println!("Removed {} invalid lines", error_count);
+    // Machine-readable formats get the same report as `validate-files`, with nothing else on stdout
+    if output_format != OutputFormat::Human {
+        let report = validate_files_with_summary_serde(&[file_path.to_path_buf()], &config)
+            .with_context(|| format!("Failed to validate file: {}", file_path.display()))?;
+        return write_report(&report, output_format, &None);
     }
-}
 
-pub fn handle_validate_file(file_path: &PathBuf, clean: bool, output_dir: &Option<PathBuf>) -> Result<()> {
     println!("Validating file: {}", file_path.display());
     
-    let _config = ValidatorConfig {
-        clean_files: clean,
-        output_dir: output_dir.clone(),
-    };
-    
     let start = Instant::now();
     let errors = validate_file_serde(file_path)
         .with_context(|| format!("Failed to validate file: {}", file_path.display()))?;
@@ -75,56 +47,399 @@ pub fn handle_validate_file(file_path: &PathBuf, clean: bool, output_dir: &Optio
         println!("✅ File is valid! Validation took {:.2?}", duration);
     } else {
         println!("❌ Found {} errors in file. Validation took {:.2?}", errors.len(), duration);
-        print_errors(&errors);
+        print_errors(&errors, DEFAULT_MAX_LINE_CONTENT_CHARS);
         
         if clean {
             print_cleaning_info(file_path, output_dir.as_ref().unwrap(), errors.len());
         }
     }
     
+    if stats {
+        let stats = line_size_stats(file_path)
+            .with_context(|| format!("Failed to compute line sizes for file: {}", file_path.display()))?;
+        println!(
+            "Valid line sizes: min {} bytes, max {} bytes, mean {:.1} bytes, p95 {} bytes",
+            stats.min_bytes, stats.max_bytes, stats.mean_bytes, stats.p95_bytes
+        );
+    }
+    
     Ok(())
 }
 
-pub fn handle_validate_files(file_paths: &[PathBuf], clean: bool, output_dir: &Option<PathBuf>) -> Result<()> {
-    println!("Validating {} files", file_paths.len());
+pub fn handle_validate_files(
+    file_paths: &[PathBuf],
+    clean: bool,
+    output_dir: &Option<PathBuf>,
+    output_format: OutputFormat,
+    report_file: &Option<PathBuf>,
+    first_line_only: bool,
+) -> Result<()> {
+    let prints_human_report = output_format == OutputFormat::Human && report_file.is_none();
+    if prints_human_report {
+        println!("Validating {} files", file_paths.len());
+    }
     
     let config = ValidatorConfig {
         clean_files: clean,
         output_dir: output_dir.clone(),
+        first_line_only,
+        ..Default::default()
     };
     
     let start = Instant::now();
-    let (summary, errors) = validate_files_with_summary_serde(file_paths, &config)
+    let report = validate_files_with_summary_serde(file_paths, &config)
         .with_context(|| "Failed to validate files")?;
     let duration = start.elapsed();
     
-    print_summary(&summary, duration);
-    
-    if !errors.is_empty() {
-        print_errors(&errors);
+    if prints_human_report {
+        print_report(&report, duration);
+    } else {
+        write_report(&report, output_format, report_file)?;
     }
     
     Ok(())
 }
 
-pub fn handle_validate_dir(dir_path: &PathBuf, clean: bool, output_dir: &Option<PathBuf>) -> Result<()> {
-    println!("Validating all ND-JSON files in: {}", dir_path.display());
+pub fn handle_validate_dir(
+    dir_path: &Path,
+    clean: bool,
+    output_dir: &Option<PathBuf>,
+    output_format: OutputFormat,
+    report_file: &Option<PathBuf>,
+    first_line_only: bool,
+) -> Result<()> {
+    let prints_human_report = output_format == OutputFormat::Human && report_file.is_none();
+    if prints_human_report {
+        println!("Validating all ND-JSON files in: {}", dir_path.display());
+    }
     
     let config = ValidatorConfig {
         clean_files: clean,
         output_dir: output_dir.clone(),
+        first_line_only,
+        ..Default::default()
     };
     
     let start = Instant::now();
-    let (summary, errors) = validate_directory_with_summary_serde(dir_path, &config)
+    let report = validate_directory_with_summary_serde(dir_path, &config)
         .with_context(|| format!("Failed to validate files in directory: {}", dir_path.display()))?;
     let duration = start.elapsed();
     
-    print_summary(&summary, duration);
+    if prints_human_report {
+        print_report(&report, duration);
+    } else {
+        write_report(&report, output_format, report_file)?;
+    }
+    
+    Ok(())
+}
+
+/// Prints the human-readable report followed by how long validation took
+fn print_report(report: &Report, duration: Duration) {
+    print!("{}", report.render(OutputFormat::Human));
+    if report.summary.is_clean() {
+        println!("✅ All files are valid! Validation took {:.2?}", duration);
+    } else {
+        println!(
+            "❌ Found {} errors in {} files. Validation took {:.2?}",
+            report.summary.total_errors, report.summary.files_with_errors, duration
+        );
+    }
+}
+
+/// Renders the report in `output_format` to `report_file`, or to stdout if there is none
+fn write_report(report: &Report, output_format: OutputFormat, report_file: &Option<PathBuf>) -> Result<()> {
+    let rendered = report.render(output_format);
+    match report_file {
+        Some(path) => {
+            fs::write(path, rendered).with_context(|| format!("Failed to write report to {}", path.display()))?;
+            println!("Wrote report to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Validates standard input. Diagnostics go to stderr so that cleaned output can be piped on.
+pub fn handle_validate_pipe(clean: bool, output_dir: &Option<PathBuf>) -> Result<()> {
+    let config = ValidatorConfig {
+        clean_files: clean,
+        output_dir: output_dir.clone(),
+        ..Default::default()
+    };
+    
+    let start = Instant::now();
+    let errors = validate_stdin_serde(&config)
+        .with_context(|| "Failed to validate standard input")?;
+    let duration = start.elapsed();
+    
+    if errors.is_empty() {
+        eprintln!("✅ Input is valid! Validation took {:.2?}", duration);
+    } else {
+        eprintln!("❌ Found {} errors in input. Validation took {:.2?}", errors.len(), duration);
+        write_errors(&mut io::stderr().lock(), &errors, DEFAULT_MAX_LINE_CONTENT_CHARS)?;
+    }
+    
+    Ok(())
+}
+
+pub fn handle_analyze(file_path: &Path) -> Result<()> {
+    println!("Analyzing fields in: {}", file_path.display());
+    
+    let frequencies = field_frequency_analysis(file_path)
+        .with_context(|| format!("Failed to analyze file: {}", file_path.display()))?;
+    
+    if frequencies.is_empty() {
+        println!("No JSON object lines found.");
+        return Ok(());
+    }
+    
+    // Most common fields first, ties broken alphabetically
+    let mut fields: Vec<_> = frequencies.into_iter().collect();
+    fields.sort_by(|(a_name, a_count), (b_name, b_count)| b_count.cmp(a_count).then(a_name.cmp(b_name)));
     
-    if !errors.is_empty() {
-        print_errors(&errors);
+    let name_width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, count) in fields {
+        println!("  {:<width$}  {}", name, count, width = name_width);
     }
     
     Ok(())
 }
+
+pub fn handle_inspect(file_path: &Path, output_format: OutputFormat) -> Result<()> {
+    if !matches!(output_format, OutputFormat::Human | OutputFormat::Json) {
+        bail!("inspect only supports human and json output");
+    }
+    
+    let size_bytes = fs::metadata(file_path)
+        .with_context(|| format!("Failed to read metadata of file: {}", file_path.display()))?
+        .len();
+    let raw = scan_raw_lines(file_path)
+        .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+    
+    // The line-based checks need UTF-8, so for other encodings only the raw facts are shown
+    let mut info = json!({
+        "file": file_path.display().to_string(),
+        "size_bytes": size_bytes,
+        "lines": raw.lines,
+        "valid_lines": null,
+        "invalid_lines": null,
+        "fields": null,
+        "min_line_bytes": null,
+        "max_line_bytes": null,
+        "newline_style": raw.newline_style(),
+        "encoding": raw.encoding(),
+        "first_line": raw.first_line.as_deref().map(|line| truncate_chars(line, DEFAULT_MAX_LINE_CONTENT_CHARS)),
+    });
+    if raw.is_utf8 {
+        let errors = validate_file_serde(file_path)
+            .with_context(|| format!("Failed to validate file: {}", file_path.display()))?;
+        let mut fields: Vec<String> = field_frequency_analysis(file_path)
+            .with_context(|| format!("Failed to analyze file: {}", file_path.display()))?
+            .into_keys()
+            .collect();
+        fields.sort();
+        let stats = line_size_stats(file_path)
+            .with_context(|| format!("Failed to compute line sizes for file: {}", file_path.display()))?;
+        
+        info["valid_lines"] = json!(raw.lines - raw.blank_lines - errors.len());
+        info["invalid_lines"] = json!(errors.len());
+        info["fields"] = json!(fields);
+        info["min_line_bytes"] = json!(stats.min_bytes);
+        info["max_line_bytes"] = json!(stats.max_bytes);
+    }
+    
+    if output_format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+    
+    let rows = [
+        ("File", "file"),
+        ("Size (bytes)", "size_bytes"),
+        ("Lines", "lines"),
+        ("Valid lines", "valid_lines"),
+        ("Invalid lines", "invalid_lines"),
+        ("Fields", "fields"),
+        ("Min line (bytes)", "min_line_bytes"),
+        ("Max line (bytes)", "max_line_bytes"),
+        ("Newline style", "newline_style"),
+        ("Encoding", "encoding"),
+        ("First line", "first_line"),
+    ];
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    for (label, key) in rows {
+        let value = match &info[key] {
+            serde_json::Value::Null => "n/a".to_string(),
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Array(items) => items.iter().filter_map(|item| item.as_str()).collect::<Vec<_>>().join(", "),
+            other => other.to_string(),
+        };
+        println!("{:<width$}  {}", label, value, width = label_width);
+    }
+    
+    Ok(())
+}
+
+/// Facts about a file that don't need its lines to be valid UTF-8
+#[derive(Default)]
+struct RawLines {
+    lines: usize,
+    blank_lines: usize,
+    lf_lines: usize,
+    crlf_lines: usize,
+    is_utf8: bool,
+    has_bom: bool,
+    first_line: Option<String>,
+}
+
+impl RawLines {
+    fn newline_style(&self) -> String {
+        match (self.lf_lines, self.crlf_lines) {
+            (0, 0) => "none".to_string(),
+            (_, 0) => "LF".to_string(),
+            (0, _) => "CRLF".to_string(),
+            (lf, crlf) => format!("mixed ({} LF, {} CRLF)", lf, crlf),
+        }
+    }
+    
+    fn encoding(&self) -> &'static str {
+        match (self.is_utf8, self.has_bom) {
+            (true, false) => "UTF-8",
+            (true, true) => "UTF-8 with BOM",
+            (false, _) => "not valid UTF-8",
+        }
+    }
+}
+
+fn scan_raw_lines(file_path: &Path) -> io::Result<RawLines> {
+    let mut reader = BufReader::new(File::open(file_path)?);
+    let mut raw = RawLines {
+        is_utf8: true,
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        raw.lines += 1;
+        
+        let mut line = buf.as_slice();
+        if let Some(rest) = line.strip_suffix(b"\n") {
+            line = rest;
+            match rest.strip_suffix(b"\r") {
+                Some(rest) => {
+                    line = rest;
+                    raw.crlf_lines += 1;
+                }
+                None => raw.lf_lines += 1,
+            }
+        }
+        
+        if raw.lines == 1 {
+            raw.has_bom = line.starts_with(b"\xEF\xBB\xBF");
+            raw.first_line = Some(String::from_utf8_lossy(line).into_owned());
+        }
+        raw.is_utf8 &= std::str::from_utf8(line).is_ok();
+        if line.iter().all(u8::is_ascii_whitespace) {
+            raw.blank_lines += 1;
+        }
+    }
+    
+    Ok(raw)
+}
+
+pub fn handle_benchmark(file_path: &Path) -> Result<()> {
+    println!("Benchmarking parsers on: {}", file_path.display());
+    
+    let start = Instant::now();
+    let serde_errors = validate_file_serde(file_path)
+        .with_context(|| format!("Failed to validate file with serde_json: {}", file_path.display()))?;
+    println!("  serde_json: {} errors in {:.2?}", serde_errors.len(), start.elapsed());
+    
+    let start = Instant::now();
+    let sonic_errors = validate_file_sonic(file_path)
+        .with_context(|| format!("Failed to validate file with sonic-rs: {}", file_path.display()))?;
+    println!("  sonic-rs:   {} errors in {:.2?}", sonic_errors.len(), start.elapsed());
+    
+    let comparison = compare_backends(file_path)
+        .with_context(|| format!("Failed to compare parsers on file: {}", file_path.display()))?;
+    println!(
+        "Agreement: {:.2}% of {} lines ({} valid, {} invalid for both)",
+        comparison.agreement_rate() * 100.0,
+        comparison.total_lines,
+        comparison.both_valid,
+        comparison.both_invalid
+    );
+    
+    if comparison.is_fully_consistent() {
+        println!("✅ Both parsers agree on every line");
+        return Ok(());
+    }
+    
+    println!(
+        "❌ Parsers disagree on {} lines ({} rejected only by serde_json, {} only by sonic-rs):",
+        comparison.disagreements.len(),
+        comparison.serde_only_invalid,
+        comparison.sonic_only_invalid
+    );
+    for disagreement in &comparison.disagreements {
+        let verdict = |error: &Option<String>| error.clone().unwrap_or_else(|| "valid".to_string());
+        println!(
+            "  line {}: serde_json: {}; sonic-rs: {}",
+            disagreement.line_number,
+            verdict(&disagreement.serde_error),
+            verdict(&disagreement.sonic_error)
+        );
+    }
+    
+    Ok(())
+}
+
+pub fn handle_generate_fixtures(output_dir: &Path, files: usize, lines: usize, error_rate: f64, seed: u64) -> Result<()> {
+    let config = CorpusConfig {
+        files,
+        lines_per_file: lines,
+        error_rate,
+        seed,
+    };
+    
+    let file_paths = generate_test_corpus(output_dir, &config)
+        .with_context(|| format!("Failed to generate fixtures in {}", output_dir.display()))?;
+    
+    println!(
+        "Wrote {} files with {} lines each to {} (seed {})",
+        file_paths.len(),
+        lines,
+        output_dir.display(),
+        seed
+    );
+    
+    Ok(())
+}
+
+pub fn handle_convert_from_csv(input: &Path, output: &Path, delimiter: char, has_header: bool) -> Result<()> {
+    if !delimiter.is_ascii() {
+        bail!("Delimiter must be a single ASCII character, got '{}'", delimiter);
+    }
+    
+    let lines_written = csv_to_ndjson(input, output, delimiter as u8, has_header)
+        .with_context(|| format!("Failed to convert CSV file: {}", input.display()))?;
+    
+    println!("Wrote {} lines to {}", lines_written, output.display());
+    
+    Ok(())
+}
+
+#[cfg(feature = "http-server")]
+pub fn handle_server(port: u16) -> Result<()> {
+    println!("Listening on port {}", port);
+    
+    let runtime = tokio::runtime::Runtime::new().with_context(|| "Failed to start async runtime")?;
+    runtime
+        .block_on(ndjson_validator::server::serve(port))
+        .with_context(|| format!("Failed to serve on port {}", port))
+}
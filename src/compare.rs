@@ -0,0 +1,115 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::config::ValidatorConfig;
+use crate::error::Result;
+use crate::validator::{check_line, is_skipped, Backend, Lines};
+
+/// A line that one backend accepts and the other rejects
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendDisagreement {
+    pub line_number: usize,
+    pub line_content: String,
+    /// Error from serde_json, or `None` if it accepted the line
+    pub serde_error: Option<String>,
+    /// Error from sonic-rs, or `None` if it accepted the line
+    pub sonic_error: Option<String>,
+}
+
+/// How often serde_json and sonic-rs agree on the lines of a file, found by `compare_backends`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComparisonResult {
+    /// Number of lines compared. Blank lines are not counted.
+    pub total_lines: usize,
+    pub both_valid: usize,
+    pub both_invalid: usize,
+    pub serde_only_invalid: usize,
+    pub sonic_only_invalid: usize,
+    pub disagreements: Vec<BackendDisagreement>,
+}
+
+impl ComparisonResult {
+    /// Share of compared lines (between 0.0 and 1.0) that both backends judged the same way. A file
+    /// without lines to compare counts as fully consistent.
+    pub fn agreement_rate(&self) -> f64 {
+        if self.total_lines == 0 {
+            return 1.0;
+        }
+        (self.both_valid + self.both_invalid) as f64 / self.total_lines as f64
+    }
+
+    /// Returns `true` if the backends accepted and rejected exactly the same lines
+    pub fn is_fully_consistent(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// Validates every line of a file with both serde_json and sonic-rs and reports where they
+/// disagree, e.g. before switching a pipeline from one backend to the other
+pub fn compare_backends(path: &Path) -> Result<ComparisonResult> {
+    let file = File::open(path)?;
+    let config = ValidatorConfig::default();
+    let mut result = ComparisonResult::default();
+
+    for line in Lines::new(BufReader::new(file), path) {
+        let line = line?;
+        if is_skipped(&line.content, line.number, &config) {
+            continue;
+        }
+        result.total_lines += 1;
+
+        let serde_error = check_line(&line.content, line.number, path, &config, Backend::Serde).map(|e| e.error);
+        let sonic_error = check_line(&line.content, line.number, path, &config, Backend::Sonic).map(|e| e.error);
+        match (&serde_error, &sonic_error) {
+            (None, None) => result.both_valid += 1,
+            (Some(_), Some(_)) => result.both_invalid += 1,
+            (serde, _) => {
+                if serde.is_some() {
+                    result.serde_only_invalid += 1;
+                } else {
+                    result.sonic_only_invalid += 1;
+                }
+                result.disagreements.push(BackendDisagreement {
+                    line_number: line.number,
+                    line_content: line.content,
+                    serde_error,
+                    sonic_error,
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_compare_backends() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.ndjson");
+        fs::write(&file_path, "{\"a\": 1}\n\n{\"a\": 1e400}\n{\"a\": }\n[1, 2]\n").unwrap();
+
+        let result = compare_backends(&file_path).unwrap();
+        assert_eq!(result.total_lines, 4);
+        assert_eq!(result.both_valid, 2);
+        assert_eq!(result.both_invalid, 1);
+        assert_eq!(result.serde_only_invalid, 1);
+        assert_eq!(result.sonic_only_invalid, 0);
+        assert_eq!(result.agreement_rate(), 0.75);
+        assert!(!result.is_fully_consistent());
+
+        let disagreement = &result.disagreements[0];
+        assert_eq!(disagreement.line_number, 3);
+        assert!(disagreement.serde_error.is_some());
+        assert_eq!(disagreement.sonic_error, None);
+
+        fs::write(&file_path, "{\"a\": 1}\n").unwrap();
+        assert!(compare_backends(&file_path).unwrap().is_fully_consistent());
+    }
+}
@@ -1,14 +1,996 @@
-use std::path::PathBuf;
+use std::env;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 
-/// Configuration options for the ND-JSON validator
-#[derive(Debug, Clone)]
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{NdJsonError, Result, ValidationError};
+use crate::report::OutputFormat;
+
+/// Configuration options for the ND-JSON validator.
+///
+/// With the default `serde` feature it serializes to and from JSON (or any other serde format),
+/// with missing fields taking their default values.
+///
+/// Build one with [`ValidatorConfig::builder`], which keeps compiling as fields are added. A struct
+/// literal ending in `..Default::default()` works too:
+///
+/// ```
+/// use std::path::PathBuf;
+/// use ndjson_validator::ValidatorConfig;
+///
+/// let built = ValidatorConfig::builder()
+///     .clean_files(true)
+///     .output_dir(PathBuf::from("cleaned"))
+///     .build();
+/// let literal = ValidatorConfig {
+///     clean_files: true,
+///     output_dir: Some(PathBuf::from("cleaned")),
+///     ..Default::default()
+/// };
+/// assert_eq!(built, literal);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct ValidatorConfig {
     /// Whether to clean files by removing invalid JSON lines
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::{Path, PathBuf};
+    /// use ndjson_validator::{process_file_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     clean_files: true,
+    ///     output_dir: Some(PathBuf::from("cleaned")),
+    ///     ..Default::default()
+    /// };
+    /// // Also writes cleaned/data.ndjson with only the valid lines
+    /// let errors = process_file_serde(Path::new("data.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
     pub clean_files: bool,
     
     /// Directory to write cleaned files to (if clean_files is true)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     clean_files: true,
+    ///     output_dir: Some(PathBuf::from("/tmp/cleaned")),
+    ///     ..Default::default()
+    /// };
+    /// // Cleaned copies keep their file names: /tmp/cleaned/a.ndjson and /tmp/cleaned/b.ndjson
+    /// validate_files_serde(&[PathBuf::from("in/a.ndjson"), PathBuf::from("in/b.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
     pub output_dir: Option<PathBuf>,
-    
+
+    /// Number of leading lines to treat as a non-JSON header. They are neither validated nor
+    /// removed when cleaning, but still count towards reported line numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     skip_header_lines: 1,
+    ///     ..Default::default()
+    /// };
+    /// let content = "exported 2024-01-01\n{\"id\": 1}\n";
+    /// assert_eq!(clean_content_serde(content, &config)?, content);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub skip_header_lines: usize,
+
+    /// Whether to store a SHA-256 hash of each invalid line in `ValidationError::line_hash`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_config_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     compute_line_hashes: true,
+    ///     ..Default::default()
+    /// };
+    /// let errors = validate_file_with_config_serde(Path::new("data.ndjson"), &config)?;
+    /// assert!(errors.iter().all(|error| error.line_hash.is_some()));
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub compute_line_hashes: bool,
+
+    /// Files larger than this many bytes are rejected with `NdJsonError::FileTooLarge`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{process_file_serde, NdJsonError, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     max_file_size_bytes: Some(64 * 1024 * 1024),
+    ///     ..Default::default()
+    /// };
+    /// match process_file_serde(Path::new("huge.ndjson"), &config) {
+    ///     Err(NdJsonError::FileTooLarge { size, .. }) => println!("skipped a {} byte file", size),
+    ///     other => println!("{:?}", other),
+    /// }
+    /// ```
+    pub max_file_size_bytes: Option<u64>,
+
+    /// Minimum number of ND-JSON files a validated directory must contain. Fewer files fail with
+    /// `NdJsonError::TooFewFiles` before any file is validated.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, NdJsonError, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     min_files_expected: Some(24),
+    ///     ..Default::default()
+    /// };
+    /// // Fails if an hourly export directory is missing files
+    /// if let Err(NdJsonError::TooFewFiles { found, .. }) = validate_directory_with_summary_serde(Path::new("exports/2024-01-01"), &config) {
+    ///     eprintln!("only {} of 24 hourly files were exported", found);
+    /// }
+    /// ```
+    pub min_files_expected: Option<usize>,
+
+    /// Whether to emit a `tracing` warning when a batch has many more files than Rayon has
+    /// threads, since scheduling lots of tiny tasks adds overhead. Has no effect without the
+    /// `tracing` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::ValidatorConfig;
+    ///
+    /// // Logs a warning through `tracing` when a batch has far more files than threads
+    /// let config = ValidatorConfig {
+    ///     warn_on_task_overflow: true,
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub warn_on_task_overflow: bool,
+
+    /// When cleaning, write this line (e.g. `{}` or `null`) in place of each invalid line instead
+    /// of dropping it, so that the cleaned output keeps the original line count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     invalid_line_replacement: Some("null".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"id\": 1}\n{\"id\": }\n{\"id\": 3}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"id\": 1}\nnull\n{\"id\": 3}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub invalid_line_replacement: Option<String>,
+
+    /// Report at most this many errors per file, and stop reading it at the next invalid line,
+    /// which shows that errors were left out. Other files in the same batch are unaffected. When
+    /// cleaning, the whole file is still scanned so that no invalid line makes it into the output,
+    /// but only this many errors are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_config_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     max_errors_per_file: Some(10),
+    ///     ..Default::default()
+    /// };
+    /// // Stops reading a badly broken file at its eleventh invalid line
+    /// let errors = validate_file_with_config_serde(Path::new("data.ndjson"), &config)?;
+    /// assert!(errors.len() <= 10);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub max_errors_per_file: Option<usize>,
+
+    /// Fail a batch with `NdJsonError::ErrorRateExceeded` as soon as the share of invalid lines
+    /// among all lines read so far (between 0.0 and 1.0) goes above this threshold
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, NdJsonError, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     max_error_rate: Some(0.01),
+    ///     ..Default::default()
+    /// };
+    /// let files = vec![PathBuf::from("a.ndjson"), PathBuf::from("b.ndjson")];
+    /// if let Err(NdJsonError::ErrorRateExceeded { current_rate, .. }) = validate_files_serde(&files, &config) {
+    ///     eprintln!("aborted, {:.1}% of lines were invalid", current_rate * 100.0);
+    /// }
+    /// ```
+    pub max_error_rate: Option<f64>,
+
+    /// Format used when a `Report` returned by the summary functions is displayed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_with_summary_serde, OutputFormat, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     output_format: OutputFormat::Json,
+    ///     ..Default::default()
+    /// };
+    /// let report = validate_files_with_summary_serde(&[PathBuf::from("data.ndjson")], &config)?;
+    /// // Prints the report as JSON
+    /// println!("{}", report);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub output_format: OutputFormat,
+
+    /// Remove every `\r` from a line before parsing it, not just a trailing one. This accepts
+    /// lines mangled by broken converters that leave stray carriage returns inside strings.
+    /// Cleaned output has them removed too, so it passes validation without this option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let content = "{\"name\": \"a\r\"}\n";
+    /// assert_eq!(clean_content_serde(content, &ValidatorConfig::default())?, "");
+    ///
+    /// let config = ValidatorConfig {
+    ///     normalize_line_endings: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(clean_content_serde(content, &config)?, "{\"name\": \"a\"}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub normalize_line_endings: bool,
+
+    /// When cleaning, sort the output lines by the value of this top-level field. Numbers sort
+    /// numerically and before strings; lines without the field go last. Header lines stay first.
+    /// The whole cleaned file is held in memory while sorting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     sort_by_field: Some("id".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"id\": 2}\n{\"name\": \"x\"}\n{\"id\": 1}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"id\": 1}\n{\"id\": 2}\n{\"name\": \"x\"}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub sort_by_field: Option<String>,
+
+    /// Top-level field used by `validate_files_partitioned_serde` to group files. Each file is
+    /// grouped by the field's value in its first valid line.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_partitioned_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     partition_by_field: Some("tenant".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let files = vec![PathBuf::from("a.ndjson"), PathBuf::from("b.ndjson")];
+    /// for (tenant, report) in validate_files_partitioned_serde(&files, &config)? {
+    ///     println!("{}: {} errors", tenant, report.summary.total_errors);
+    /// }
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub partition_by_field: Option<String>,
+
+    /// Treat the first non-empty line after any `skip_header_lines` as a JSON metadata header
+    /// describing the schema. It is not validated and is copied to cleaned output as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     skip_schema_header: true,
+    ///     ..Default::default()
+    /// };
+    /// let content = "{\"schema\": \"v2\"}\n{\"id\": 1}\n";
+    /// assert_eq!(clean_content_serde(content, &config)?, content);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub skip_schema_header: bool,
+
+    /// Validate the files found in a directory in lexicographic path order, so that errors are
+    /// reported in the same order on every platform. Defaults to `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, ValidatorConfig};
+    ///
+    /// // Keep the order the file system lists the files in
+    /// let config = ValidatorConfig {
+    ///     sort_files: false,
+    ///     ..Default::default()
+    /// };
+    /// let report = validate_directory_with_summary_serde(Path::new("data"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub sort_files: bool,
+
+    /// Reject lines whose arrays and objects are nested deeper than this, even if they are
+    /// otherwise valid JSON. Deeply nested input can overflow the stack of recursive parsers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     max_nesting_depth: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"a\": [1]}\n{\"a\": [[1]]}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"a\": [1]}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub max_nesting_depth: Option<usize>,
+
+    /// Report objects that repeat a key. Both parsers accept them and keep only one of the values,
+    /// which usually means the producer has a bug.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     detect_duplicate_keys: true,
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"id\": 1}\n{\"id\": 1, \"id\": 2}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"id\": 1}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub detect_duplicate_keys: bool,
+
+    /// When cleaning a batch of files, also write a `_validation_summary.json` file to the output
+    /// directory with the summary, a timestamp and the list of processed files
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     clean_files: true,
+    ///     output_dir: Some(PathBuf::from("cleaned")),
+    ///     write_summary_file: true,
+    ///     ..Default::default()
+    /// };
+    /// // Also writes cleaned/_validation_summary.json
+    /// validate_files_with_summary_serde(&[PathBuf::from("data.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub write_summary_file: bool,
+
+    /// Treat lines starting with `//` (after leading whitespace) as comments. They are neither
+    /// validated nor removed when cleaning. Off by default, since comments are not part of ND-JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     allow_comment_lines: true,
+    ///     ..Default::default()
+    /// };
+    /// let content = "// fixture for issue 12\n{\"id\": 1}\n";
+    /// assert_eq!(clean_content_serde(content, &config)?, content);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub allow_comment_lines: bool,
+
+    /// Store `sha256:<hex digest>` in `ValidationError::line_content` instead of the invalid line
+    /// itself, so errors can be logged without leaking personal data contained in the lines
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_config_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     hash_line_content: true,
+    ///     ..Default::default()
+    /// };
+    /// let errors = validate_file_with_config_serde(Path::new("users.ndjson"), &config)?;
+    /// assert!(errors.iter().all(|error| error.line_content.starts_with("sha256:")));
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub hash_line_content: bool,
+
+    /// Require every valid line to have the same top-level keys as the first valid line. Lines with
+    /// missing or extra keys are reported as errors, which catches schema drift in a stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     enforce_consistent_schema: true,
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"id\": 1}\n{\"uuid\": \"a\"}\n{\"id\": 2}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"id\": 1}\n{\"id\": 2}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub enforce_consistent_schema: bool,
+
+    /// Report lines containing numbers that an `f64` cannot represent exactly, such as floats with
+    /// more significant digits than it holds or integers too large for 64 bits. Such numbers
+    /// silently change value depending on which parser reads them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     check_float_precision: true,
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"x\": 0.5}\n{\"x\": 0.12345678901234567890}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"x\": 0.5}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub check_float_precision: bool,
+
+    /// SQLite database to log every validation error to, in a `validation_errors` table that is
+    /// created if needed and appended to. Requires the `sqlite` feature; without it, validating
+    /// with this set fails with `NdJsonError::InvalidConfig`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     sqlite_error_log: Some(PathBuf::from("errors.db")),
+    ///     ..Default::default()
+    /// };
+    /// validate_files_with_summary_serde(&[PathBuf::from("data.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub sqlite_error_log: Option<PathBuf>,
+
+    /// Warn about files whose only line is a JSON array, which usually means a list of objects
+    /// was written as a single JSON document instead of one object per line. The warnings are
+    /// collected in `Report::warnings`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     detect_single_line_array: true,
+    ///     ..Default::default()
+    /// };
+    /// let report = validate_files_with_summary_serde(&[PathBuf::from("export.json")], &config)?;
+    /// for warning in &report.warnings {
+    ///     println!("{}: {}", warning.file_path.display(), warning.message);
+    /// }
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub detect_single_line_array: bool,
+
+    /// Warn about files that mix `\n` and `\r\n` line endings, which usually means they were
+    /// concatenated from different sources. The warning gives the first line whose ending differs
+    /// from the first line's.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_warnings_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     check_newline_consistency: true,
+    ///     ..Default::default()
+    /// };
+    /// let (_errors, warnings) = validate_file_with_warnings_serde(Path::new("merged.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub check_newline_consistency: bool,
+
+    /// Only validate the lines in this range of 1-based line numbers (start inclusive, end
+    /// exclusive). Lines before it are still read, to count them, but not checked, and reading
+    /// stops at its end. Line numbers in errors stay absolute, so several ranges of one large file
+    /// can be validated in parallel. `NdjsonValidatorIter` and `StreamingValidator` honour it too.
+    /// Cleaning would keep the unchecked lines, so cleaning with this set, including with
+    /// `validate_and_stream_valid_serde`, fails with `NdJsonError::InvalidConfig`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_config_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     line_range: Some(1_000_001..2_000_001),
+    ///     ..Default::default()
+    /// };
+    /// // Errors in the second million lines, numbered from the start of the file
+    /// let errors = validate_file_with_config_serde(Path::new("large.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub line_range: Option<Range<usize>>,
+
+    /// Split files into small (under 1 MB), medium and large (100 MB and over) buckets by size, and
+    /// validate the large ones first with fewer threads to limit memory pressure, then the rest
+    /// with full parallelism. Helps throughput when file sizes vary widely. Errors are still
+    /// reported in file order.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     bucket_by_size: true,
+    ///     ..Default::default()
+    /// };
+    /// let files = vec![PathBuf::from("small.ndjson"), PathBuf::from("5gb-dump.ndjson")];
+    /// // 5gb-dump.ndjson is validated first, with fewer threads
+    /// let errors = validate_files_serde(&files, &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub bucket_by_size: bool,
+
+    /// Only accept lines whose root value is an object or array, as stricter readings of JSON Lines
+    /// require. Bare scalars like `null`, `true`, `1` or `"text"` are reported as errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     strict_jsonlines: true,
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"id\": 1}\n42\n[1, 2]\n", &config)?;
+    /// assert_eq!(cleaned, "{\"id\": 1}\n[1, 2]\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub strict_jsonlines: bool,
+
+    /// Decode lines that are not valid UTF-8 as Latin-1 (ISO-8859-1) instead of failing with
+    /// `NdJsonError::UnsupportedEncoding`. Meant for exports from legacy systems that are labelled
+    /// as UTF-8 but aren't. Each byte maps to the code point of the same value, so decoding never
+    /// fails, and cleaned files are written as UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{process_file_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     encoding_fallback: true,
+    ///     ..Default::default()
+    /// };
+    /// // Reads "Jos\xe9" as "José" instead of failing with `NdJsonError::UnsupportedEncoding`
+    /// let errors = process_file_serde(Path::new("legacy-export.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub encoding_fallback: bool,
+
+    /// Sort the errors of a batch by file path and then line number. Errors otherwise follow the
+    /// order the files were given in, which for a directory depends on `sort_files`; with this set,
+    /// the same set of files always gives byte-identical output, which keeps diff-based CI checks
+    /// reliable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     stable_output: true,
+    ///     ..Default::default()
+    /// };
+    /// let files = vec![PathBuf::from("b.ndjson"), PathBuf::from("a.ndjson")];
+    /// // Errors in a.ndjson come first
+    /// let errors = validate_files_serde(&files, &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub stable_output: bool,
+
+    /// JSON file to rewrite after each file of a batch has been validated, with `files_done`,
+    /// `files_total`, `errors_so_far` and `current_file` (the file just finished). It is written to
+    /// a temporary file next to it and renamed into place, so other processes never see a partial
+    /// write.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     progress_file: Some(PathBuf::from("progress.json")),
+    ///     ..Default::default()
+    /// };
+    /// // Meanwhile, `cat progress.json` shows {"files_done":1,"files_total":2,...}
+    /// validate_files_serde(&[PathBuf::from("a.ndjson"), PathBuf::from("b.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub progress_file: Option<PathBuf>,
+
+    /// Report every warning, such as those enabled by `detect_single_line_array` and
+    /// `check_newline_consistency`, as an error instead, so that it counts towards the summary and
+    /// fails zero-error CI gates. Warnings are promoted after cleaning, so the lines they point at
+    /// are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_warnings_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     check_newline_consistency: true,
+    ///     warnings_as_errors: true,
+    ///     ..Default::default()
+    /// };
+    /// let (errors, warnings) = validate_file_with_warnings_serde(Path::new("merged.ndjson"), &config)?;
+    /// assert!(warnings.is_empty());
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub warnings_as_errors: bool,
+
+    /// Warn about lines that look like compressed data rather than JSON: base64-encoded gzip
+    /// (starting with `H4sI`) or, with `encoding_fallback`, raw gzip bytes. Such lines are still
+    /// reported as invalid, but the warning explains why. Raw gzip is rarely valid UTF-8, so
+    /// without the fallback the file fails with `NdJsonError::UnsupportedEncoding` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_warnings_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     detect_compressed_lines: true,
+    ///     ..Default::default()
+    /// };
+    /// // A line like `H4sIAAAAAAAAA6tWyk6tVLJSUCpLzClNVaoFAGl3pKcQAAAA` is invalid, and also warned about
+    /// let (errors, warnings) = validate_file_with_warnings_serde(Path::new("events.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub detect_compressed_lines: bool,
+
+    /// Only validate the first line of each file that isn't blank, a comment or one of the
+    /// `skip_header_lines`, and stop reading there. A quick way to check that every file of a large
+    /// directory starts with a valid record. Summaries are marked with
+    /// `ValidationSummary::partial_scan`. Cleaning would keep the unchecked lines, so cleaning with
+    /// this set fails with `NdJsonError::InvalidConfig`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     first_line_only: true,
+    ///     ..Default::default()
+    /// };
+    /// let report = validate_directory_with_summary_serde(Path::new("archive"), &config)?;
+    /// assert!(report.summary.partial_scan);
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub first_line_only: bool,
+
+    /// Report objects with fewer than this many top-level keys, which usually means a serializer
+    /// wrote a truncated record. Lines that aren't objects aren't checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     min_fields_per_object: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"a\": 1, \"b\": 2}\n{\"a\": 1}\n[1]\n", &config)?;
+    /// assert_eq!(cleaned, "{\"a\": 1, \"b\": 2}\n[1]\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub min_fields_per_object: Option<usize>,
+
+    /// Sort the errors of a batch by the position of their file in the input list and then by line
+    /// number. Errors already come file by file in input order, but warnings promoted by
+    /// `warnings_as_errors` are otherwise appended after all of them. Takes precedence over
+    /// `stable_output`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     order_errors_by_input: true,
+    ///     check_newline_consistency: true,
+    ///     warnings_as_errors: true,
+    ///     ..Default::default()
+    /// };
+    /// let files = vec![PathBuf::from("b.ndjson"), PathBuf::from("a.ndjson")];
+    /// // All errors in b.ndjson, promoted warnings included, come before those in a.ndjson
+    /// let report = validate_files_with_summary_serde(&files, &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub order_errors_by_input: bool,
+
+    /// Warn about `\uXXXX` escapes of printable ASCII characters, such as `\u0041` for `A`, which
+    /// usually come from an overly defensive serializer. Escapes of quotes, backslashes, control
+    /// characters and non-ASCII characters are left alone, since serializers like Python's
+    /// `json.dumps` escape non-ASCII text on purpose. Only lines that are otherwise valid are
+    /// checked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_warnings_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     strict_unicode: true,
+    ///     ..Default::default()
+    /// };
+    /// // `{"name": "\u0041lice"}` is valid, but warned about
+    /// let (errors, warnings) = validate_file_with_warnings_serde(Path::new("export.ndjson"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub strict_unicode: bool,
+
+    /// Number of worker threads used to validate a batch of files. Defaults to Rayon's global pool,
+    /// which has one thread per CPU; when set, each batch runs in a pool of its own with this many
+    /// threads, which keeps a validator from taking over a machine it shares with other work.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use ndjson_validator::{validate_files_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig::builder().threads(2).build();
+    /// let errors = validate_files_serde(&[PathBuf::from("a.ndjson"), PathBuf::from("b.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub threads: Option<usize>,
+
+    /// Skip files and directories whose name starts with `.`, such as `.git/` or `.DS_Store`, when
+    /// looking for files in a directory. Hidden directories are not descended into at all. The
+    /// directory being validated is always searched, even if it is hidden itself. Defaults to
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     skip_hidden: false,
+    ///     ..Default::default()
+    /// };
+    /// // Also validates data/.backup.ndjson
+    /// let report = validate_directory_with_summary_serde(Path::new("data"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub skip_hidden: bool,
+
+    /// Also pick up named pipes (FIFOs) with an ND-JSON name when looking for files in a directory,
+    /// which are skipped like other special files otherwise. A pipe given directly is always read.
+    /// This allows `mkfifo events.ndjson && producer > events.ndjson &` followed by validating the
+    /// pipe.
+    ///
+    /// A pipe can only be read once, so cleaning it or running the whole-file warning checks on it
+    /// returns `NdJsonError::InvalidConfig` instead of waiting for a second writer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     allow_named_pipes: true,
+    ///     ..Default::default()
+    /// };
+    /// // Reads incoming/events.ndjson even if it's a pipe a producer is writing to
+    /// let report = validate_directory_with_summary_serde(Path::new("incoming"), &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub allow_named_pipes: bool,
+
+    /// Report at most this many errors for a batch, including promoted warnings, and skip the
+    /// files after the one that goes past it. Files are validated a thread pool's worth at a time,
+    /// so the same files are skipped on every run. Each file is read in full, so without
+    /// `max_errors_per_file` one large file can still hold many errors in memory; together the two
+    /// bound it. Skipped files are not cleaned either. Summaries of a batch that left errors out
+    /// are marked with `ValidationSummary::truncated`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_directory_with_summary_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     max_errors_per_file: Some(100),
+    ///     max_total_errors: Some(10_000),
+    ///     ..Default::default()
+    /// };
+    /// let report = validate_directory_with_summary_serde(Path::new("archive"), &config)?;
+    /// if report.summary.truncated {
+    ///     println!("stopped early, there may be more errors");
+    /// }
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub max_total_errors: Option<usize>,
+
+    /// Report only the first invalid line of each file and stop reading at the next one, for quick
+    /// checks that only need to know whether a file is valid. Applies to each file of a batch
+    /// separately, like `max_errors_per_file: Some(1)`, and like it still cleans the whole file
+    /// when cleaning. The single-pass paths that write valid lines as they go, such as
+    /// `validate_and_stream_valid_serde`, stop at the first invalid line instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ndjson_validator::{validate_file_with_config_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     fail_fast: true,
+    ///     ..Default::default()
+    /// };
+    /// let is_valid = validate_file_with_config_serde(Path::new("data.ndjson"), &config)?.is_empty();
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub fail_fast: bool,
+
+    /// When cleaning, rewrite the numbers in each valid line into plain decimal form, so that
+    /// integer-valued floats like `1.0` and `1e3` become `1` and `1000` and `2.5E-3` becomes
+    /// `0.0025`. The digits are kept exactly and the rest of the line, including key order, is left
+    /// unchanged. Exponents that would need more than 64 zeros spelled out are left as written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndjson_validator::{clean_content_serde, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     normalize_numbers: true,
+    ///     ..Default::default()
+    /// };
+    /// let cleaned = clean_content_serde("{\"a\": 1.0, \"b\": 1e3}\n", &config)?;
+    /// assert_eq!(cleaned, "{\"a\": 1, \"b\": 1000}\n");
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub normalize_numbers: bool,
+
+    /// Called with the path of each file before it is validated. Runs on Rayon worker threads, so
+    /// it may be called for several files at once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::{Path, PathBuf};
+    /// use std::sync::Arc;
+    /// use ndjson_validator::{validate_files_serde, FileHook, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     pre_file_hook: Some(FileHook(Arc::new(|path: &Path| println!("starting {}", path.display())))),
+    ///     ..Default::default()
+    /// };
+    /// validate_files_serde(&[PathBuf::from("data.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub pre_file_hook: Option<PreFileHook>,
+
+    /// Called with the path and errors of each file once it has been validated (and cleaned, if
+    /// cleaning is enabled). Not called for files that fail to validate, e.g. because they can't
+    /// be read. Runs on Rayon worker threads, like `pre_file_hook`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::{Path, PathBuf};
+    /// use std::sync::Arc;
+    /// use ndjson_validator::{validate_files_serde, FileHook, ValidationError, ValidatorConfig};
+    ///
+    /// let config = ValidatorConfig {
+    ///     post_file_hook: Some(FileHook(Arc::new(|path: &Path, errors: &[ValidationError]| {
+    ///         println!("{}: {} errors", path.display(), errors.len())
+    ///     }))),
+    ///     ..Default::default()
+    /// };
+    /// validate_files_serde(&[PathBuf::from("data.ndjson")], &config)?;
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub post_file_hook: Option<PostFileHook>,
+}
+
+/// A callback stored in a `ValidatorConfig`.
+///
+/// Hooks are skipped when a config is (de)serialized, and only compare equal to clones of
+/// themselves.
+pub struct FileHook<F: ?Sized>(pub Arc<F>);
+
+/// Hook for `ValidatorConfig::pre_file_hook`
+pub type PreFileHook = FileHook<dyn Fn(&Path) + Send + Sync>;
+
+/// Hook for `ValidatorConfig::post_file_hook`
+pub type PostFileHook = FileHook<dyn Fn(&Path, &[ValidationError]) + Send + Sync>;
+
+impl<F: ?Sized> Clone for FileHook<F> {
+    fn clone(&self) -> Self {
+        FileHook(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> PartialEq for FileHook<F> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for FileHook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FileHook(..)")
+    }
 }
 
 impl Default for ValidatorConfig {
@@ -16,6 +998,1025 @@ impl Default for ValidatorConfig {
         Self {
             clean_files: false,
             output_dir: None,
+            skip_header_lines: 0,
+            compute_line_hashes: false,
+            max_file_size_bytes: None,
+            min_files_expected: None,
+            warn_on_task_overflow: false,
+            invalid_line_replacement: None,
+            max_errors_per_file: None,
+            max_error_rate: None,
+            output_format: OutputFormat::default(),
+            normalize_line_endings: false,
+            sort_by_field: None,
+            partition_by_field: None,
+            skip_schema_header: false,
+            sort_files: true,
+            max_nesting_depth: None,
+            detect_duplicate_keys: false,
+            write_summary_file: false,
+            allow_comment_lines: false,
+            hash_line_content: false,
+            enforce_consistent_schema: false,
+            check_float_precision: false,
+            sqlite_error_log: None,
+            detect_single_line_array: false,
+            check_newline_consistency: false,
+            line_range: None,
+            bucket_by_size: false,
+            strict_jsonlines: false,
+            encoding_fallback: false,
+            stable_output: false,
+            progress_file: None,
+            warnings_as_errors: false,
+            detect_compressed_lines: false,
+            first_line_only: false,
+            min_fields_per_object: None,
+            order_errors_by_input: false,
+            strict_unicode: false,
+            threads: None,
+            skip_hidden: true,
+            allow_named_pipes: false,
+            max_total_errors: None,
+            fail_fast: false,
+            normalize_numbers: false,
+            pre_file_hook: None,
+            post_file_hook: None,
+        }
+    }
+}
+
+impl ValidatorConfig {
+    /// Starts building a config from the defaults
+    pub fn builder() -> ValidatorConfigBuilder {
+        ValidatorConfigBuilder::default()
+    }
+
+    /// Builds a config from `NDJSON_*` environment variables, using the default for any that are
+    /// not set:
+    ///
+    /// | Variable | Field |
+    /// |---|---|
+    /// | `NDJSON_CLEAN_FILES` | `clean_files` |
+    /// | `NDJSON_OUTPUT_DIR` | `output_dir` |
+    /// | `NDJSON_SKIP_HEADER_LINES` | `skip_header_lines` |
+    /// | `NDJSON_COMPUTE_LINE_HASHES` | `compute_line_hashes` |
+    /// | `NDJSON_MAX_FILE_SIZE_BYTES` | `max_file_size_bytes` |
+    /// | `NDJSON_MIN_FILES_EXPECTED` | `min_files_expected` |
+    /// | `NDJSON_WARN_ON_TASK_OVERFLOW` | `warn_on_task_overflow` |
+    /// | `NDJSON_INVALID_LINE_REPLACEMENT` | `invalid_line_replacement` |
+    /// | `NDJSON_MAX_ERRORS_PER_FILE` | `max_errors_per_file` |
+    /// | `NDJSON_MAX_ERROR_RATE` | `max_error_rate` |
+    /// | `NDJSON_OUTPUT_FORMAT` | `output_format` (`human`, `json`, `csv` or `ndjson`) |
+    /// | `NDJSON_NORMALIZE_LINE_ENDINGS` | `normalize_line_endings` |
+    /// | `NDJSON_SORT_BY_FIELD` | `sort_by_field` |
+    /// | `NDJSON_PARTITION_BY_FIELD` | `partition_by_field` |
+    /// | `NDJSON_SKIP_SCHEMA_HEADER` | `skip_schema_header` |
+    /// | `NDJSON_SORT_FILES` | `sort_files` |
+    /// | `NDJSON_MAX_NESTING_DEPTH` | `max_nesting_depth` |
+    /// | `NDJSON_DETECT_DUPLICATE_KEYS` | `detect_duplicate_keys` |
+    /// | `NDJSON_WRITE_SUMMARY_FILE` | `write_summary_file` |
+    /// | `NDJSON_ALLOW_COMMENT_LINES` | `allow_comment_lines` |
+    /// | `NDJSON_HASH_LINE_CONTENT` | `hash_line_content` |
+    /// | `NDJSON_ENFORCE_CONSISTENT_SCHEMA` | `enforce_consistent_schema` |
+    /// | `NDJSON_CHECK_FLOAT_PRECISION` | `check_float_precision` |
+    /// | `NDJSON_SQLITE_ERROR_LOG` | `sqlite_error_log` |
+    /// | `NDJSON_DETECT_SINGLE_LINE_ARRAY` | `detect_single_line_array` |
+    /// | `NDJSON_CHECK_NEWLINE_CONSISTENCY` | `check_newline_consistency` |
+    /// | `NDJSON_LINE_RANGE` | `line_range` |
+    /// | `NDJSON_BUCKET_BY_SIZE` | `bucket_by_size` |
+    /// | `NDJSON_STRICT_JSONLINES` | `strict_jsonlines` |
+    /// | `NDJSON_ENCODING_FALLBACK` | `encoding_fallback` |
+    /// | `NDJSON_STABLE_OUTPUT` | `stable_output` |
+    /// | `NDJSON_PROGRESS_FILE` | `progress_file` |
+    /// | `NDJSON_WARNINGS_AS_ERRORS` | `warnings_as_errors` |
+    /// | `NDJSON_DETECT_COMPRESSED_LINES` | `detect_compressed_lines` |
+    /// | `NDJSON_FIRST_LINE_ONLY` | `first_line_only` |
+    /// | `NDJSON_MIN_FIELDS_PER_OBJECT` | `min_fields_per_object` |
+    /// | `NDJSON_ORDER_ERRORS_BY_INPUT` | `order_errors_by_input` |
+    /// | `NDJSON_STRICT_UNICODE` | `strict_unicode` |
+    /// | `NDJSON_THREADS` | `threads` |
+    /// | `NDJSON_SKIP_HIDDEN` | `skip_hidden` |
+    /// | `NDJSON_ALLOW_NAMED_PIPES` | `allow_named_pipes` |
+    /// | `NDJSON_MAX_TOTAL_ERRORS` | `max_total_errors` |
+    /// | `NDJSON_FAIL_FAST` | `fail_fast` |
+    /// | `NDJSON_NORMALIZE_NUMBERS` | `normalize_numbers` |
+    ///
+    /// Booleans accept `true`/`false` and `1`/`0`, and ranges are written as `start..end`. A value
+    /// that cannot be parsed fails with `NdJsonError::InvalidConfig`. File hooks cannot be set from
+    /// the environment.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_with(|name| env::var(name))
+    }
+
+    /// Like `from_env`, but looks each variable up with `lookup` instead of in the environment of
+    /// the process, e.g. to read them from a `.env` file. `lookup` returns the same errors as
+    /// `std::env::var`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use std::env::VarError;
+    /// use ndjson_validator::ValidatorConfig;
+    ///
+    /// let vars = HashMap::from([("NDJSON_MAX_ERRORS_PER_FILE", "100")]);
+    /// let lookup = |name: &str| vars.get(name).map(|value| value.to_string()).ok_or(VarError::NotPresent);
+    /// let config = ValidatorConfig::from_env_with(lookup)?;
+    /// assert_eq!(config.max_errors_per_file, Some(100));
+    /// # Ok::<(), ndjson_validator::NdJsonError>(())
+    /// ```
+    pub fn from_env_with(lookup: impl Fn(&str) -> std::result::Result<String, env::VarError>) -> Result<Self> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            clean_files: env_bool(&lookup, "NDJSON_CLEAN_FILES")?.unwrap_or(defaults.clean_files),
+            output_dir: env_var(&lookup, "NDJSON_OUTPUT_DIR")?.or(defaults.output_dir),
+            skip_header_lines: env_var(&lookup, "NDJSON_SKIP_HEADER_LINES")?.unwrap_or(defaults.skip_header_lines),
+            compute_line_hashes: env_bool(&lookup, "NDJSON_COMPUTE_LINE_HASHES")?.unwrap_or(defaults.compute_line_hashes),
+            max_file_size_bytes: env_var(&lookup, "NDJSON_MAX_FILE_SIZE_BYTES")?.or(defaults.max_file_size_bytes),
+            min_files_expected: env_var(&lookup, "NDJSON_MIN_FILES_EXPECTED")?.or(defaults.min_files_expected),
+            warn_on_task_overflow: env_bool(&lookup, "NDJSON_WARN_ON_TASK_OVERFLOW")?.unwrap_or(defaults.warn_on_task_overflow),
+            invalid_line_replacement: env_var(&lookup, "NDJSON_INVALID_LINE_REPLACEMENT")?.or(defaults.invalid_line_replacement),
+            max_errors_per_file: env_var(&lookup, "NDJSON_MAX_ERRORS_PER_FILE")?.or(defaults.max_errors_per_file),
+            max_error_rate: env_var(&lookup, "NDJSON_MAX_ERROR_RATE")?.or(defaults.max_error_rate),
+            output_format: env_var(&lookup, "NDJSON_OUTPUT_FORMAT")?.unwrap_or(defaults.output_format),
+            normalize_line_endings: env_bool(&lookup, "NDJSON_NORMALIZE_LINE_ENDINGS")?.unwrap_or(defaults.normalize_line_endings),
+            sort_by_field: env_var(&lookup, "NDJSON_SORT_BY_FIELD")?.or(defaults.sort_by_field),
+            partition_by_field: env_var(&lookup, "NDJSON_PARTITION_BY_FIELD")?.or(defaults.partition_by_field),
+            skip_schema_header: env_bool(&lookup, "NDJSON_SKIP_SCHEMA_HEADER")?.unwrap_or(defaults.skip_schema_header),
+            sort_files: env_bool(&lookup, "NDJSON_SORT_FILES")?.unwrap_or(defaults.sort_files),
+            max_nesting_depth: env_var(&lookup, "NDJSON_MAX_NESTING_DEPTH")?.or(defaults.max_nesting_depth),
+            detect_duplicate_keys: env_bool(&lookup, "NDJSON_DETECT_DUPLICATE_KEYS")?.unwrap_or(defaults.detect_duplicate_keys),
+            write_summary_file: env_bool(&lookup, "NDJSON_WRITE_SUMMARY_FILE")?.unwrap_or(defaults.write_summary_file),
+            allow_comment_lines: env_bool(&lookup, "NDJSON_ALLOW_COMMENT_LINES")?.unwrap_or(defaults.allow_comment_lines),
+            hash_line_content: env_bool(&lookup, "NDJSON_HASH_LINE_CONTENT")?.unwrap_or(defaults.hash_line_content),
+            enforce_consistent_schema: env_bool(&lookup, "NDJSON_ENFORCE_CONSISTENT_SCHEMA")?
+                .unwrap_or(defaults.enforce_consistent_schema),
+            check_float_precision: env_bool(&lookup, "NDJSON_CHECK_FLOAT_PRECISION")?.unwrap_or(defaults.check_float_precision),
+            sqlite_error_log: env_var(&lookup, "NDJSON_SQLITE_ERROR_LOG")?.or(defaults.sqlite_error_log),
+            detect_single_line_array: env_bool(&lookup, "NDJSON_DETECT_SINGLE_LINE_ARRAY")?
+                .unwrap_or(defaults.detect_single_line_array),
+            check_newline_consistency: env_bool(&lookup, "NDJSON_CHECK_NEWLINE_CONSISTENCY")?
+                .unwrap_or(defaults.check_newline_consistency),
+            line_range: env_range(&lookup, "NDJSON_LINE_RANGE")?.or(defaults.line_range),
+            bucket_by_size: env_bool(&lookup, "NDJSON_BUCKET_BY_SIZE")?.unwrap_or(defaults.bucket_by_size),
+            strict_jsonlines: env_bool(&lookup, "NDJSON_STRICT_JSONLINES")?.unwrap_or(defaults.strict_jsonlines),
+            encoding_fallback: env_bool(&lookup, "NDJSON_ENCODING_FALLBACK")?.unwrap_or(defaults.encoding_fallback),
+            stable_output: env_bool(&lookup, "NDJSON_STABLE_OUTPUT")?.unwrap_or(defaults.stable_output),
+            progress_file: env_var(&lookup, "NDJSON_PROGRESS_FILE")?.or(defaults.progress_file),
+            warnings_as_errors: env_bool(&lookup, "NDJSON_WARNINGS_AS_ERRORS")?.unwrap_or(defaults.warnings_as_errors),
+            detect_compressed_lines: env_bool(&lookup, "NDJSON_DETECT_COMPRESSED_LINES")?.unwrap_or(defaults.detect_compressed_lines),
+            first_line_only: env_bool(&lookup, "NDJSON_FIRST_LINE_ONLY")?.unwrap_or(defaults.first_line_only),
+            min_fields_per_object: env_var(&lookup, "NDJSON_MIN_FIELDS_PER_OBJECT")?.or(defaults.min_fields_per_object),
+            order_errors_by_input: env_bool(&lookup, "NDJSON_ORDER_ERRORS_BY_INPUT")?.unwrap_or(defaults.order_errors_by_input),
+            strict_unicode: env_bool(&lookup, "NDJSON_STRICT_UNICODE")?.unwrap_or(defaults.strict_unicode),
+            threads: env_var(&lookup, "NDJSON_THREADS")?.or(defaults.threads),
+            skip_hidden: env_bool(&lookup, "NDJSON_SKIP_HIDDEN")?.unwrap_or(defaults.skip_hidden),
+            allow_named_pipes: env_bool(&lookup, "NDJSON_ALLOW_NAMED_PIPES")?.unwrap_or(defaults.allow_named_pipes),
+            max_total_errors: env_var(&lookup, "NDJSON_MAX_TOTAL_ERRORS")?.or(defaults.max_total_errors),
+            fail_fast: env_bool(&lookup, "NDJSON_FAIL_FAST")?.unwrap_or(defaults.fail_fast),
+            normalize_numbers: env_bool(&lookup, "NDJSON_NORMALIZE_NUMBERS")?.unwrap_or(defaults.normalize_numbers),
+            pre_file_hook: defaults.pre_file_hook,
+            post_file_hook: defaults.post_file_hook,
+        })
+    }
+
+    /// Layers `overlay` on top of this config, e.g. CLI flags over a config file.
+    ///
+    /// Each field set in `overlay` wins, even if it is set to its default value, and every other
+    /// field is taken from `self`.
+    pub fn merge(&self, overlay: &ValidatorConfigOverlay) -> ValidatorConfig {
+        ValidatorConfig {
+            clean_files: layer(&self.clean_files, &overlay.clean_files),
+            output_dir: layer(&self.output_dir, &overlay.output_dir),
+            skip_header_lines: layer(&self.skip_header_lines, &overlay.skip_header_lines),
+            compute_line_hashes: layer(&self.compute_line_hashes, &overlay.compute_line_hashes),
+            max_file_size_bytes: layer(&self.max_file_size_bytes, &overlay.max_file_size_bytes),
+            min_files_expected: layer(&self.min_files_expected, &overlay.min_files_expected),
+            warn_on_task_overflow: layer(&self.warn_on_task_overflow, &overlay.warn_on_task_overflow),
+            invalid_line_replacement: layer(&self.invalid_line_replacement, &overlay.invalid_line_replacement),
+            max_errors_per_file: layer(&self.max_errors_per_file, &overlay.max_errors_per_file),
+            max_error_rate: layer(&self.max_error_rate, &overlay.max_error_rate),
+            output_format: layer(&self.output_format, &overlay.output_format),
+            normalize_line_endings: layer(&self.normalize_line_endings, &overlay.normalize_line_endings),
+            sort_by_field: layer(&self.sort_by_field, &overlay.sort_by_field),
+            partition_by_field: layer(&self.partition_by_field, &overlay.partition_by_field),
+            skip_schema_header: layer(&self.skip_schema_header, &overlay.skip_schema_header),
+            sort_files: layer(&self.sort_files, &overlay.sort_files),
+            max_nesting_depth: layer(&self.max_nesting_depth, &overlay.max_nesting_depth),
+            detect_duplicate_keys: layer(&self.detect_duplicate_keys, &overlay.detect_duplicate_keys),
+            write_summary_file: layer(&self.write_summary_file, &overlay.write_summary_file),
+            allow_comment_lines: layer(&self.allow_comment_lines, &overlay.allow_comment_lines),
+            hash_line_content: layer(&self.hash_line_content, &overlay.hash_line_content),
+            enforce_consistent_schema: layer(&self.enforce_consistent_schema, &overlay.enforce_consistent_schema),
+            check_float_precision: layer(&self.check_float_precision, &overlay.check_float_precision),
+            sqlite_error_log: layer(&self.sqlite_error_log, &overlay.sqlite_error_log),
+            detect_single_line_array: layer(&self.detect_single_line_array, &overlay.detect_single_line_array),
+            check_newline_consistency: layer(&self.check_newline_consistency, &overlay.check_newline_consistency),
+            line_range: layer(&self.line_range, &overlay.line_range),
+            bucket_by_size: layer(&self.bucket_by_size, &overlay.bucket_by_size),
+            strict_jsonlines: layer(&self.strict_jsonlines, &overlay.strict_jsonlines),
+            encoding_fallback: layer(&self.encoding_fallback, &overlay.encoding_fallback),
+            stable_output: layer(&self.stable_output, &overlay.stable_output),
+            progress_file: layer(&self.progress_file, &overlay.progress_file),
+            warnings_as_errors: layer(&self.warnings_as_errors, &overlay.warnings_as_errors),
+            detect_compressed_lines: layer(&self.detect_compressed_lines, &overlay.detect_compressed_lines),
+            first_line_only: layer(&self.first_line_only, &overlay.first_line_only),
+            min_fields_per_object: layer(&self.min_fields_per_object, &overlay.min_fields_per_object),
+            order_errors_by_input: layer(&self.order_errors_by_input, &overlay.order_errors_by_input),
+            strict_unicode: layer(&self.strict_unicode, &overlay.strict_unicode),
+            threads: layer(&self.threads, &overlay.threads),
+            skip_hidden: layer(&self.skip_hidden, &overlay.skip_hidden),
+            allow_named_pipes: layer(&self.allow_named_pipes, &overlay.allow_named_pipes),
+            max_total_errors: layer(&self.max_total_errors, &overlay.max_total_errors),
+            fail_fast: layer(&self.fail_fast, &overlay.fail_fast),
+            normalize_numbers: layer(&self.normalize_numbers, &overlay.normalize_numbers),
+            pre_file_hook: layer(&self.pre_file_hook, &overlay.pre_file_hook),
+            post_file_hook: layer(&self.post_file_hook, &overlay.post_file_hook),
         }
     }
+
+    /// Most errors reported for a single file, from `max_errors_per_file` and `fail_fast`
+    pub(crate) fn error_limit_per_file(&self) -> Option<usize> {
+        match (self.fail_fast, self.max_errors_per_file) {
+            (true, limit) => Some(limit.map_or(1, |limit| limit.min(1))),
+            (false, limit) => limit,
+        }
+    }
+
+    /// Eagerly starts Rayon's global thread pool when `prewarm` is true, so that the first batch
+    /// validated with this config does not pay for spawning the worker threads.
+    ///
+    /// The global pool can only be built once. If it already exists it is already warm, so that
+    /// case is not treated as an error.
+    pub fn with_prewarm_threadpool(self, prewarm: bool) -> Self {
+        if prewarm {
+            let _ = rayon::ThreadPoolBuilder::new().build_global();
+        }
+        self
+    }
+}
+
+/// Builds a `ValidatorConfig` step by step, see `ValidatorConfig::builder`
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorConfigBuilder {
+    config: ValidatorConfig,
+}
+
+impl ValidatorConfigBuilder {
+    /// Sets `ValidatorConfig::clean_files`
+    pub fn clean_files(mut self, clean_files: bool) -> Self {
+        self.config.clean_files = clean_files;
+        self
+    }
+
+    /// Sets `ValidatorConfig::output_dir`
+    pub fn output_dir(mut self, output_dir: PathBuf) -> Self {
+        self.config.output_dir = Some(output_dir);
+        self
+    }
+
+    /// Sets `ValidatorConfig::skip_header_lines`
+    pub fn skip_header_lines(mut self, skip_header_lines: usize) -> Self {
+        self.config.skip_header_lines = skip_header_lines;
+        self
+    }
+
+    /// Sets `ValidatorConfig::compute_line_hashes`
+    pub fn compute_line_hashes(mut self, compute_line_hashes: bool) -> Self {
+        self.config.compute_line_hashes = compute_line_hashes;
+        self
+    }
+
+    /// Sets `ValidatorConfig::max_file_size_bytes`
+    pub fn max_file_size_bytes(mut self, max_file_size_bytes: u64) -> Self {
+        self.config.max_file_size_bytes = Some(max_file_size_bytes);
+        self
+    }
+
+    /// Sets `ValidatorConfig::min_files_expected`
+    pub fn min_files_expected(mut self, min_files_expected: usize) -> Self {
+        self.config.min_files_expected = Some(min_files_expected);
+        self
+    }
+
+    /// Sets `ValidatorConfig::warn_on_task_overflow`
+    pub fn warn_on_task_overflow(mut self, warn_on_task_overflow: bool) -> Self {
+        self.config.warn_on_task_overflow = warn_on_task_overflow;
+        self
+    }
+
+    /// Sets `ValidatorConfig::invalid_line_replacement`
+    pub fn invalid_line_replacement(mut self, invalid_line_replacement: String) -> Self {
+        self.config.invalid_line_replacement = Some(invalid_line_replacement);
+        self
+    }
+
+    /// Sets `ValidatorConfig::max_errors_per_file`
+    pub fn max_errors_per_file(mut self, max_errors_per_file: usize) -> Self {
+        self.config.max_errors_per_file = Some(max_errors_per_file);
+        self
+    }
+
+    /// Sets `ValidatorConfig::max_error_rate`
+    pub fn max_error_rate(mut self, max_error_rate: f64) -> Self {
+        self.config.max_error_rate = Some(max_error_rate);
+        self
+    }
+
+    /// Sets `ValidatorConfig::output_format`
+    pub fn output_format(mut self, output_format: OutputFormat) -> Self {
+        self.config.output_format = output_format;
+        self
+    }
+
+    /// Sets `ValidatorConfig::normalize_line_endings`
+    pub fn normalize_line_endings(mut self, normalize_line_endings: bool) -> Self {
+        self.config.normalize_line_endings = normalize_line_endings;
+        self
+    }
+
+    /// Sets `ValidatorConfig::sort_by_field`
+    pub fn sort_by_field(mut self, sort_by_field: String) -> Self {
+        self.config.sort_by_field = Some(sort_by_field);
+        self
+    }
+
+    /// Sets `ValidatorConfig::partition_by_field`
+    pub fn partition_by_field(mut self, partition_by_field: String) -> Self {
+        self.config.partition_by_field = Some(partition_by_field);
+        self
+    }
+
+    /// Sets `ValidatorConfig::skip_schema_header`
+    pub fn skip_schema_header(mut self, skip_schema_header: bool) -> Self {
+        self.config.skip_schema_header = skip_schema_header;
+        self
+    }
+
+    /// Sets `ValidatorConfig::sort_files`
+    pub fn sort_files(mut self, sort_files: bool) -> Self {
+        self.config.sort_files = sort_files;
+        self
+    }
+
+    /// Sets `ValidatorConfig::max_nesting_depth`
+    pub fn max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.config.max_nesting_depth = Some(max_nesting_depth);
+        self
+    }
+
+    /// Sets `ValidatorConfig::detect_duplicate_keys`
+    pub fn detect_duplicate_keys(mut self, detect_duplicate_keys: bool) -> Self {
+        self.config.detect_duplicate_keys = detect_duplicate_keys;
+        self
+    }
+
+    /// Sets `ValidatorConfig::write_summary_file`
+    pub fn write_summary_file(mut self, write_summary_file: bool) -> Self {
+        self.config.write_summary_file = write_summary_file;
+        self
+    }
+
+    /// Sets `ValidatorConfig::allow_comment_lines`
+    pub fn allow_comment_lines(mut self, allow_comment_lines: bool) -> Self {
+        self.config.allow_comment_lines = allow_comment_lines;
+        self
+    }
+
+    /// Sets `ValidatorConfig::hash_line_content`
+    pub fn hash_line_content(mut self, hash_line_content: bool) -> Self {
+        self.config.hash_line_content = hash_line_content;
+        self
+    }
+
+    /// Sets `ValidatorConfig::enforce_consistent_schema`
+    pub fn enforce_consistent_schema(mut self, enforce_consistent_schema: bool) -> Self {
+        self.config.enforce_consistent_schema = enforce_consistent_schema;
+        self
+    }
+
+    /// Sets `ValidatorConfig::check_float_precision`
+    pub fn check_float_precision(mut self, check_float_precision: bool) -> Self {
+        self.config.check_float_precision = check_float_precision;
+        self
+    }
+
+    /// Sets `ValidatorConfig::sqlite_error_log`
+    pub fn sqlite_error_log(mut self, sqlite_error_log: PathBuf) -> Self {
+        self.config.sqlite_error_log = Some(sqlite_error_log);
+        self
+    }
+
+    /// Sets `ValidatorConfig::detect_single_line_array`
+    pub fn detect_single_line_array(mut self, detect_single_line_array: bool) -> Self {
+        self.config.detect_single_line_array = detect_single_line_array;
+        self
+    }
+
+    /// Sets `ValidatorConfig::check_newline_consistency`
+    pub fn check_newline_consistency(mut self, check_newline_consistency: bool) -> Self {
+        self.config.check_newline_consistency = check_newline_consistency;
+        self
+    }
+
+    /// Sets `ValidatorConfig::line_range`
+    pub fn line_range(mut self, line_range: Range<usize>) -> Self {
+        self.config.line_range = Some(line_range);
+        self
+    }
+
+    /// Sets `ValidatorConfig::bucket_by_size`
+    pub fn bucket_by_size(mut self, bucket_by_size: bool) -> Self {
+        self.config.bucket_by_size = bucket_by_size;
+        self
+    }
+
+    /// Sets `ValidatorConfig::strict_jsonlines`
+    pub fn strict_jsonlines(mut self, strict_jsonlines: bool) -> Self {
+        self.config.strict_jsonlines = strict_jsonlines;
+        self
+    }
+
+    /// Sets `ValidatorConfig::encoding_fallback`
+    pub fn encoding_fallback(mut self, encoding_fallback: bool) -> Self {
+        self.config.encoding_fallback = encoding_fallback;
+        self
+    }
+
+    /// Sets `ValidatorConfig::stable_output`
+    pub fn stable_output(mut self, stable_output: bool) -> Self {
+        self.config.stable_output = stable_output;
+        self
+    }
+
+    /// Sets `ValidatorConfig::progress_file`
+    pub fn progress_file(mut self, progress_file: PathBuf) -> Self {
+        self.config.progress_file = Some(progress_file);
+        self
+    }
+
+    /// Sets `ValidatorConfig::warnings_as_errors`
+    pub fn warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+        self.config.warnings_as_errors = warnings_as_errors;
+        self
+    }
+
+    /// Sets `ValidatorConfig::detect_compressed_lines`
+    pub fn detect_compressed_lines(mut self, detect_compressed_lines: bool) -> Self {
+        self.config.detect_compressed_lines = detect_compressed_lines;
+        self
+    }
+
+    /// Sets `ValidatorConfig::first_line_only`
+    pub fn first_line_only(mut self, first_line_only: bool) -> Self {
+        self.config.first_line_only = first_line_only;
+        self
+    }
+
+    /// Sets `ValidatorConfig::min_fields_per_object`
+    pub fn min_fields_per_object(mut self, min_fields_per_object: usize) -> Self {
+        self.config.min_fields_per_object = Some(min_fields_per_object);
+        self
+    }
+
+    /// Sets `ValidatorConfig::order_errors_by_input`
+    pub fn order_errors_by_input(mut self, order_errors_by_input: bool) -> Self {
+        self.config.order_errors_by_input = order_errors_by_input;
+        self
+    }
+
+    /// Sets `ValidatorConfig::strict_unicode`
+    pub fn strict_unicode(mut self, strict_unicode: bool) -> Self {
+        self.config.strict_unicode = strict_unicode;
+        self
+    }
+
+    /// Sets `ValidatorConfig::threads`
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = Some(threads);
+        self
+    }
+
+    /// Sets `ValidatorConfig::skip_hidden`
+    pub fn skip_hidden(mut self, skip_hidden: bool) -> Self {
+        self.config.skip_hidden = skip_hidden;
+        self
+    }
+
+    /// Sets `ValidatorConfig::allow_named_pipes`
+    pub fn allow_named_pipes(mut self, allow_named_pipes: bool) -> Self {
+        self.config.allow_named_pipes = allow_named_pipes;
+        self
+    }
+
+    /// Sets `ValidatorConfig::max_total_errors`
+    pub fn max_total_errors(mut self, max_total_errors: usize) -> Self {
+        self.config.max_total_errors = Some(max_total_errors);
+        self
+    }
+
+    /// Sets `ValidatorConfig::fail_fast`
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.config.fail_fast = fail_fast;
+        self
+    }
+
+    /// Sets `ValidatorConfig::normalize_numbers`
+    pub fn normalize_numbers(mut self, normalize_numbers: bool) -> Self {
+        self.config.normalize_numbers = normalize_numbers;
+        self
+    }
+
+    /// Sets `ValidatorConfig::pre_file_hook`
+    pub fn pre_file_hook(mut self, pre_file_hook: PreFileHook) -> Self {
+        self.config.pre_file_hook = Some(pre_file_hook);
+        self
+    }
+
+    /// Sets `ValidatorConfig::post_file_hook`
+    pub fn post_file_hook(mut self, post_file_hook: PostFileHook) -> Self {
+        self.config.post_file_hook = Some(post_file_hook);
+        self
+    }
+
+    /// Returns the finished config
+    pub fn build(self) -> ValidatorConfig {
+        self.config
+    }
+}
+
+/// Fields to change when layering one config on another with [`ValidatorConfig::merge`].
+///
+/// A field left as `None` keeps the value of the base config, so unlike a second
+/// `ValidatorConfig`, an overlay can also set a field back to its default. Fields that are
+/// themselves optional are doubly wrapped: `Some(None)` clears the base value.
+///
+/// ```
+/// use ndjson_validator::{ValidatorConfig, ValidatorConfigOverlay};
+///
+/// let from_file = ValidatorConfig {
+///     clean_files: true,
+///     max_errors_per_file: Some(10),
+///     ..Default::default()
+/// };
+/// let from_flags = ValidatorConfigOverlay {
+///     clean_files: Some(false),
+///     ..Default::default()
+/// };
+/// let config = from_file.merge(&from_flags);
+/// assert!(!config.clean_files);
+/// assert_eq!(config.max_errors_per_file, Some(10));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidatorConfigOverlay {
+    pub clean_files: Option<bool>,
+    pub output_dir: Option<Option<PathBuf>>,
+    pub skip_header_lines: Option<usize>,
+    pub compute_line_hashes: Option<bool>,
+    pub max_file_size_bytes: Option<Option<u64>>,
+    pub min_files_expected: Option<Option<usize>>,
+    pub warn_on_task_overflow: Option<bool>,
+    pub invalid_line_replacement: Option<Option<String>>,
+    pub max_errors_per_file: Option<Option<usize>>,
+    pub max_error_rate: Option<Option<f64>>,
+    pub output_format: Option<OutputFormat>,
+    pub normalize_line_endings: Option<bool>,
+    pub sort_by_field: Option<Option<String>>,
+    pub partition_by_field: Option<Option<String>>,
+    pub skip_schema_header: Option<bool>,
+    pub sort_files: Option<bool>,
+    pub max_nesting_depth: Option<Option<usize>>,
+    pub detect_duplicate_keys: Option<bool>,
+    pub write_summary_file: Option<bool>,
+    pub allow_comment_lines: Option<bool>,
+    pub hash_line_content: Option<bool>,
+    pub enforce_consistent_schema: Option<bool>,
+    pub check_float_precision: Option<bool>,
+    pub sqlite_error_log: Option<Option<PathBuf>>,
+    pub detect_single_line_array: Option<bool>,
+    pub check_newline_consistency: Option<bool>,
+    pub line_range: Option<Option<Range<usize>>>,
+    pub bucket_by_size: Option<bool>,
+    pub strict_jsonlines: Option<bool>,
+    pub encoding_fallback: Option<bool>,
+    pub stable_output: Option<bool>,
+    pub progress_file: Option<Option<PathBuf>>,
+    pub warnings_as_errors: Option<bool>,
+    pub detect_compressed_lines: Option<bool>,
+    pub first_line_only: Option<bool>,
+    pub min_fields_per_object: Option<Option<usize>>,
+    pub order_errors_by_input: Option<bool>,
+    pub strict_unicode: Option<bool>,
+    pub threads: Option<Option<usize>>,
+    pub skip_hidden: Option<bool>,
+    pub allow_named_pipes: Option<bool>,
+    pub max_total_errors: Option<Option<usize>>,
+    pub fail_fast: Option<bool>,
+    pub normalize_numbers: Option<bool>,
+    pub pre_file_hook: Option<Option<PreFileHook>>,
+    pub post_file_hook: Option<Option<PostFileHook>>,
+}
+
+impl From<ValidatorConfig> for ValidatorConfigOverlay {
+    /// An overlay that sets every field, so merging it yields `config` itself
+    fn from(config: ValidatorConfig) -> Self {
+        Self {
+            clean_files: Some(config.clean_files),
+            output_dir: Some(config.output_dir),
+            skip_header_lines: Some(config.skip_header_lines),
+            compute_line_hashes: Some(config.compute_line_hashes),
+            max_file_size_bytes: Some(config.max_file_size_bytes),
+            min_files_expected: Some(config.min_files_expected),
+            warn_on_task_overflow: Some(config.warn_on_task_overflow),
+            invalid_line_replacement: Some(config.invalid_line_replacement),
+            max_errors_per_file: Some(config.max_errors_per_file),
+            max_error_rate: Some(config.max_error_rate),
+            output_format: Some(config.output_format),
+            normalize_line_endings: Some(config.normalize_line_endings),
+            sort_by_field: Some(config.sort_by_field),
+            partition_by_field: Some(config.partition_by_field),
+            skip_schema_header: Some(config.skip_schema_header),
+            sort_files: Some(config.sort_files),
+            max_nesting_depth: Some(config.max_nesting_depth),
+            detect_duplicate_keys: Some(config.detect_duplicate_keys),
+            write_summary_file: Some(config.write_summary_file),
+            allow_comment_lines: Some(config.allow_comment_lines),
+            hash_line_content: Some(config.hash_line_content),
+            enforce_consistent_schema: Some(config.enforce_consistent_schema),
+            check_float_precision: Some(config.check_float_precision),
+            sqlite_error_log: Some(config.sqlite_error_log),
+            detect_single_line_array: Some(config.detect_single_line_array),
+            check_newline_consistency: Some(config.check_newline_consistency),
+            line_range: Some(config.line_range),
+            bucket_by_size: Some(config.bucket_by_size),
+            strict_jsonlines: Some(config.strict_jsonlines),
+            encoding_fallback: Some(config.encoding_fallback),
+            stable_output: Some(config.stable_output),
+            progress_file: Some(config.progress_file),
+            warnings_as_errors: Some(config.warnings_as_errors),
+            detect_compressed_lines: Some(config.detect_compressed_lines),
+            first_line_only: Some(config.first_line_only),
+            min_fields_per_object: Some(config.min_fields_per_object),
+            order_errors_by_input: Some(config.order_errors_by_input),
+            strict_unicode: Some(config.strict_unicode),
+            threads: Some(config.threads),
+            skip_hidden: Some(config.skip_hidden),
+            allow_named_pipes: Some(config.allow_named_pipes),
+            max_total_errors: Some(config.max_total_errors),
+            fail_fast: Some(config.fail_fast),
+            normalize_numbers: Some(config.normalize_numbers),
+            pre_file_hook: Some(config.pre_file_hook),
+            post_file_hook: Some(config.post_file_hook),
+        }
+    }
+}
+
+/// Value of a field in a merged config: `overlay` if it is set, else `base`
+fn layer<T: Clone>(base: &T, overlay: &Option<T>) -> T {
+    overlay.as_ref().unwrap_or(base).clone()
+}
+
+/// Looks up an environment variable, as `std::env::var` does
+trait EnvLookup: Fn(&str) -> std::result::Result<String, env::VarError> {}
+
+impl<F: Fn(&str) -> std::result::Result<String, env::VarError>> EnvLookup for F {}
+
+/// Reads and parses an environment variable, returning `None` if it is not set
+fn env_var<T: FromStr>(lookup: &impl EnvLookup, name: &str) -> Result<Option<T>> {
+    let value = match lookup(name) {
+        Ok(value) => value,
+        Err(env::VarError::NotPresent) => return Ok(None),
+        Err(env::VarError::NotUnicode(_)) => {
+            return Err(NdJsonError::InvalidConfig(format!("{} is not valid unicode", name)))
+        }
+    };
+
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| NdJsonError::InvalidConfig(format!("{}={:?} is not a valid value", name, value)))
+}
+
+/// Like `env_var`, but also accepts `1` and `0` for booleans
+fn env_bool(lookup: &impl EnvLookup, name: &str) -> Result<Option<bool>> {
+    match env_var::<String>(lookup, name)?.as_deref() {
+        None => Ok(None),
+        Some("1") => Ok(Some(true)),
+        Some("0") => Ok(Some(false)),
+        Some(_) => env_var(lookup, name),
+    }
+}
+
+/// Reads a `start..end` range from an environment variable
+fn env_range(lookup: &impl EnvLookup, name: &str) -> Result<Option<Range<usize>>> {
+    let Some(value) = env_var::<String>(lookup, name)? else {
+        return Ok(None);
+    };
+
+    let bounds = value
+        .split_once("..")
+        .and_then(|(start, end)| Some(start.trim().parse().ok()?..end.trim().parse().ok()?));
+    match bounds {
+        Some(range) => Ok(Some(range)),
+        None => Err(NdJsonError::InvalidConfig(format!(
+            "{} must be a range like 10..20, got {}",
+            name, value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::option;
+    use proptest::prelude::*;
+
+    fn arb_output_format() -> impl Strategy<Value = OutputFormat> {
+        prop_oneof![
+            Just(OutputFormat::Human),
+            Just(OutputFormat::Json),
+            Just(OutputFormat::Csv),
+            Just(OutputFormat::Ndjson),
+            Just(OutputFormat::Html),
+        ]
+    }
+
+    // Built field by field rather than derived, so that adding a field without covering it here
+    // is a compile error
+    fn arb_config() -> impl Strategy<Value = ValidatorConfig> {
+        let first = (
+            any::<bool>(),
+            option::of("[a-z0-9_/.]{1,30}".prop_map(PathBuf::from)),
+            any::<usize>(),
+            any::<bool>(),
+            option::of(any::<u64>()),
+            option::of(any::<usize>()),
+            any::<bool>(),
+            option::of(".*"),
+            option::of(any::<usize>()),
+            // serde_json's default float parser can be off by one bit for long decimals, while
+            // rates with three digits always read back exactly
+            option::of((0..=1000u32).prop_map(|n| f64::from(n) / 1000.0)),
+        );
+        let second = (
+            arb_output_format(),
+            any::<bool>(),
+            option::of(".*"),
+            option::of(".*"),
+            any::<bool>(),
+            any::<bool>(),
+            option::of(any::<usize>()),
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+        );
+        let third = (
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            option::of("[a-z0-9_/.]{1,30}".prop_map(PathBuf::from)),
+            any::<bool>(),
+            any::<bool>(),
+            option::of((any::<usize>(), any::<usize>()).prop_map(|(start, end)| start..end)),
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            option::of("[a-z0-9_/.]{1,30}".prop_map(PathBuf::from)),
+        );
+        let fourth = (
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            option::of(any::<usize>()),
+            any::<bool>(),
+            any::<bool>(),
+            option::of(any::<usize>()),
+            any::<bool>(),
+            any::<bool>(),
+            option::of(any::<usize>()),
+            any::<bool>(),
+            any::<bool>(),
+        );
+
+        (first, second, third, fourth).prop_map(
+            |(
+                (
+                    clean_files,
+                    output_dir,
+                    skip_header_lines,
+                    compute_line_hashes,
+                    max_file_size_bytes,
+                    min_files_expected,
+                    warn_on_task_overflow,
+                    invalid_line_replacement,
+                    max_errors_per_file,
+                    max_error_rate,
+                ),
+                (
+                    output_format,
+                    normalize_line_endings,
+                    sort_by_field,
+                    partition_by_field,
+                    skip_schema_header,
+                    sort_files,
+                    max_nesting_depth,
+                    detect_duplicate_keys,
+                    write_summary_file,
+                    allow_comment_lines,
+                ),
+                (
+                    hash_line_content,
+                    enforce_consistent_schema,
+                    check_float_precision,
+                    sqlite_error_log,
+                    detect_single_line_array,
+                    check_newline_consistency,
+                    line_range,
+                    bucket_by_size,
+                    strict_jsonlines,
+                    encoding_fallback,
+                    stable_output,
+                    progress_file,
+                ),
+                (
+                    warnings_as_errors,
+                    detect_compressed_lines,
+                    first_line_only,
+                    min_fields_per_object,
+                    order_errors_by_input,
+                    strict_unicode,
+                    threads,
+                    skip_hidden,
+                    allow_named_pipes,
+                    max_total_errors,
+                    fail_fast,
+                    normalize_numbers,
+                ),
+            )| ValidatorConfig {
+                clean_files,
+                output_dir,
+                skip_header_lines,
+                compute_line_hashes,
+                max_file_size_bytes,
+                min_files_expected,
+                warn_on_task_overflow,
+                invalid_line_replacement,
+                max_errors_per_file,
+                max_error_rate,
+                output_format,
+                normalize_line_endings,
+                sort_by_field,
+                partition_by_field,
+                skip_schema_header,
+                sort_files,
+                max_nesting_depth,
+                detect_duplicate_keys,
+                write_summary_file,
+                allow_comment_lines,
+                hash_line_content,
+                enforce_consistent_schema,
+                check_float_precision,
+                sqlite_error_log,
+                detect_single_line_array,
+                check_newline_consistency,
+                line_range,
+                bucket_by_size,
+                strict_jsonlines,
+                encoding_fallback,
+                stable_output,
+                progress_file,
+                warnings_as_errors,
+                detect_compressed_lines,
+                first_line_only,
+                min_fields_per_object,
+                order_errors_by_input,
+                strict_unicode,
+                threads,
+                skip_hidden,
+                allow_named_pipes,
+                max_total_errors,
+                fail_fast,
+                normalize_numbers,
+                // Closures can't be generated or serialized
+                pre_file_hook: None,
+                post_file_hook: None,
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        #[cfg(feature = "serde")]
+        fn test_serde_round_trip(config in arb_config()) {
+            let json = serde_json::to_string(&config).unwrap();
+            let parsed: ValidatorConfig = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(parsed, config);
+        }
+
+        #[test]
+        fn test_merge_onto_default_is_identity(config in arb_config()) {
+            prop_assert_eq!(ValidatorConfig::default().merge(&config.clone().into()), config);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_missing_fields_use_defaults() {
+        let config: ValidatorConfig = serde_json::from_str("{\"clean_files\": true, \"output_format\": \"csv\"}").unwrap();
+        assert_eq!(
+            config,
+            ValidatorConfig {
+                clean_files: true,
+                output_format: OutputFormat::Csv,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = ValidatorConfig {
+            clean_files: true,
+            skip_header_lines: 2,
+            sort_files: false,
+            max_errors_per_file: Some(10),
+            ..Default::default()
+        };
+        let overlay = ValidatorConfigOverlay {
+            skip_header_lines: Some(1),
+            output_format: Some(OutputFormat::Json),
+            max_errors_per_file: Some(Some(5)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            base.merge(&overlay),
+            ValidatorConfig {
+                clean_files: true,
+                skip_header_lines: 1,
+                sort_files: false,
+                max_errors_per_file: Some(5),
+                output_format: OutputFormat::Json,
+                ..Default::default()
+            }
+        );
+        assert_eq!(base.merge(&ValidatorConfigOverlay::default()), base);
+
+        // Setting a field to its default value still overrides the base
+        let reset = ValidatorConfigOverlay {
+            clean_files: Some(false),
+            sort_files: Some(true),
+            max_errors_per_file: Some(None),
+            ..Default::default()
+        };
+        let merged = base.merge(&reset);
+        assert!(!merged.clean_files);
+        assert!(merged.sort_files);
+        assert_eq!(merged.max_errors_per_file, None);
+        assert_eq!(merged.skip_header_lines, 2);
+    }
+
+    /// Looks variables up in `vars` rather than the environment shared by all tests
+    fn from_vars(vars: &[(&str, &str)]) -> Result<ValidatorConfig> {
+        ValidatorConfig::from_env_with(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+                .ok_or(env::VarError::NotPresent)
+        })
+    }
+
+    #[test]
+    fn test_from_env() {
+        assert_eq!(from_vars(&[]).unwrap(), ValidatorConfig::default());
+
+        let config = from_vars(&[
+            ("NDJSON_CLEAN_FILES", "1"),
+            ("NDJSON_OUTPUT_DIR", "/tmp/out"),
+            ("NDJSON_MAX_ERRORS_PER_FILE", "100"),
+            ("NDJSON_MAX_ERROR_RATE", "0.25"),
+            ("NDJSON_OUTPUT_FORMAT", "json"),
+            ("NDJSON_NORMALIZE_LINE_ENDINGS", "true"),
+            ("NDJSON_LINE_RANGE", "10..20"),
+        ])
+        .unwrap();
+        assert!(config.clean_files);
+        assert_eq!(config.output_dir, Some(PathBuf::from("/tmp/out")));
+        assert_eq!(config.max_errors_per_file, Some(100));
+        assert_eq!(config.max_error_rate, Some(0.25));
+        assert_eq!(config.output_format, OutputFormat::Json);
+        assert!(config.normalize_line_endings);
+        assert_eq!(config.line_range, Some(10..20));
+
+        match from_vars(&[("NDJSON_MAX_ERRORS_PER_FILE", "lots")]) {
+            Err(NdJsonError::InvalidConfig(message)) => assert!(message.contains("NDJSON_MAX_ERRORS_PER_FILE")),
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+        assert!(matches!(from_vars(&[("NDJSON_LINE_RANGE", "10")]), Err(NdJsonError::InvalidConfig(_))));
+        assert!(matches!(from_vars(&[("NDJSON_CLEAN_FILES", "yes")]), Err(NdJsonError::InvalidConfig(_))));
+
+        let not_unicode = ValidatorConfig::from_env_with(|_| Err(env::VarError::NotUnicode("\u{fffd}".into())));
+        assert!(matches!(not_unicode, Err(NdJsonError::InvalidConfig(_))));
+    }
 }
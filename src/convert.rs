@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use csv::{ReaderBuilder, StringRecord};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::{Number, Value};
+
+use crate::error::{NdJsonError, Result};
+use crate::float_precision::find_imprecise_float;
+
+/// Converts a CSV file to ND-JSON, returning the number of lines written.
+///
+/// With `has_header`, the first row supplies the keys and every following row becomes a JSON
+/// object with its fields in column order. Without it, every row becomes a JSON array. Fields that
+/// are valid JSON numbers are written as numbers, everything else as strings. Quoted fields may
+/// contain the delimiter and newlines.
+pub fn csv_to_ndjson(input: &Path, output: &Path, delimiter: u8, has_header: bool) -> Result<usize> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_header)
+        .from_path(input)?;
+    let headers = if has_header { Some(reader.headers()?.clone()) } else { None };
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut lines_written = 0;
+
+    for record in reader.records() {
+        let row = Row {
+            headers: headers.as_ref(),
+            record: &record?,
+        };
+        serde_json::to_writer(&mut writer, &row).map_err(std::io::Error::from)?;
+        writeln!(writer)?;
+        lines_written += 1;
+    }
+
+    writer.flush()?;
+    Ok(lines_written)
+}
+
+/// Converts a file holding a single JSON array to ND-JSON with one element per line, returning the
+/// number of lines written. This fixes files flagged by `ValidatorConfig::detect_single_line_array`.
+pub fn json_array_to_ndjson(input: &Path, output: &Path) -> Result<usize> {
+    let reader = BufReader::new(File::open(input)?);
+    let values: Vec<Value> = serde_json::from_reader(reader).map_err(|error| NdJsonError::JsonParse {
+        file: input.display().to_string(),
+        line: error.line(),
+        error,
+    })?;
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    for value in &values {
+        serde_json::to_writer(&mut writer, value).map_err(std::io::Error::from)?;
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(values.len())
+}
+
+/// A CSV row, serialized as an object keyed by the headers if there are any, or as an array
+struct Row<'a> {
+    headers: Option<&'a StringRecord>,
+    record: &'a StringRecord,
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.headers {
+            Some(headers) => {
+                let mut map = serializer.serialize_map(Some(self.record.len()))?;
+                for (key, field) in headers.iter().zip(self.record) {
+                    map.serialize_entry(key, &field_value(field))?;
+                }
+                map.end()
+            }
+            None => {
+                let mut seq = serializer.serialize_seq(Some(self.record.len()))?;
+                for field in self.record {
+                    seq.serialize_element(&field_value(field))?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+/// JSON value of a CSV field. Only fields that are already valid JSON numbers become numbers, so
+/// values like `007` or `1e` stay strings. So do numbers padded with whitespace and numbers that
+/// an `f64` can't hold exactly, like long IDs, since converting them would change the value.
+fn field_value(field: &str) -> Value {
+    let exact = field.trim() == field && find_imprecise_float(field).is_none();
+    match serde_json::from_str::<Number>(field) {
+        Ok(number) if exact => Value::Number(number),
+        _ => Value::String(field.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_csv_to_ndjson() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("people.csv");
+        let output = temp_dir.path().join("people.ndjson");
+        fs::write(&input, "name;age;zip;note\nAlice;30;007;\"likes ; and\nnewlines\"\n\"Bob \"\"B\"\"\";2.5e3;10;\n").unwrap();
+
+        assert_eq!(csv_to_ndjson(&input, &output, b';', true).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "{\"name\":\"Alice\",\"age\":30,\"zip\":\"007\",\"note\":\"likes ; and\\nnewlines\"}\n\
+             {\"name\":\"Bob \\\"B\\\"\",\"age\":2500.0,\"zip\":10,\"note\":\"\"}\n"
+        );
+
+        assert_eq!(csv_to_ndjson(&input, &output, b';', false).unwrap(), 3);
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(content.lines().next().unwrap(), "[\"name\",\"age\",\"zip\",\"note\"]");
+    }
+
+    #[test]
+    fn test_csv_to_ndjson_keeps_inexact_numbers_as_strings() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("ids.csv");
+        let output = temp_dir.path().join("ids.ndjson");
+        fs::write(&input, "id,age,count\n123456789012345678901234567890, 30,12345678901234567890\n").unwrap();
+
+        assert_eq!(csv_to_ndjson(&input, &output, b',', true).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "{\"id\":\"123456789012345678901234567890\",\"age\":\" 30\",\"count\":12345678901234567890}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_array_to_ndjson() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("array.json");
+        let output = temp_dir.path().join("array.ndjson");
+        fs::write(&input, "[{\"a\": 1},\n [2, 3], \"x\"]").unwrap();
+
+        assert_eq!(json_array_to_ndjson(&input, &output).unwrap(), 3);
+        assert_eq!(fs::read_to_string(&output).unwrap(), "{\"a\":1}\n[2,3]\n\"x\"\n");
+
+        fs::write(&input, "{\"a\": 1}").unwrap();
+        assert!(matches!(json_array_to_ndjson(&input, &output), Err(NdJsonError::JsonParse { .. })));
+    }
+
+    #[test]
+    fn test_csv_to_ndjson_rejects_ragged_rows() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("ragged.csv");
+        fs::write(&input, "a,b\n1,2,3\n").unwrap();
+
+        let result = csv_to_ndjson(&input, &temp_dir.path().join("ragged.ndjson"), b',', true);
+        assert!(matches!(result, Err(NdJsonError::Csv(_))));
+    }
+}
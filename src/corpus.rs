@@ -0,0 +1,129 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::{NdJsonError, Result};
+
+/// Shape of a generated test corpus
+#[derive(Debug, Clone)]
+pub struct CorpusConfig {
+    /// Number of files to write
+    pub files: usize,
+    /// Number of lines in each file
+    pub lines_per_file: usize,
+    /// Probability (between 0.0 and 1.0) that a line is invalid JSON
+    pub error_rate: f64,
+    /// Seed for the random number generator. The same seed always produces the same corpus.
+    pub seed: u64,
+}
+
+impl Default for CorpusConfig {
+    fn default() -> Self {
+        Self {
+            files: 10,
+            lines_per_file: 1000,
+            error_rate: 0.01,
+            seed: 42,
+        }
+    }
+}
+
+/// Writes a reproducible set of ND-JSON files with a mix of valid and invalid lines to
+/// `output_dir`, returning the paths of the files written
+pub fn generate_test_corpus(output_dir: &Path, config: &CorpusConfig) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(output_dir)
+        .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut file_paths = Vec::with_capacity(config.files);
+
+    for i in 0..config.files {
+        let file_path = output_dir.join(format!("test_file_{}.ndjson", i));
+        let mut writer = BufWriter::new(File::create(&file_path)?);
+
+        for id in 0..config.lines_per_file {
+            if rng.gen::<f64>() < config.error_rate {
+                writeln!(writer, "{}", invalid_line(&mut rng, id))?;
+            } else {
+                writeln!(writer, "{}", valid_line(&mut rng, id))?;
+            }
+        }
+
+        writer.flush()?;
+        file_paths.push(file_path);
+    }
+
+    Ok(file_paths)
+}
+
+fn valid_line(rng: &mut StdRng, id: usize) -> String {
+    format!(
+        r#"{{"id": {}, "name": "user_{}", "score": {:.3}, "active": {}, "tags": ["a", "b"]}}"#,
+        id,
+        rng.gen_range(0..100_000),
+        rng.gen::<f64>() * 100.0,
+        rng.gen::<bool>()
+    )
+}
+
+fn invalid_line(rng: &mut StdRng, id: usize) -> String {
+    let user = rng.gen_range(0..100_000);
+    match rng.gen_range(0..5) {
+        // Missing colon after a key
+        0 => format!(r#"{{"id": {}, "name" "user_{}"}}"#, id, user),
+        // Unclosed brace
+        1 => format!(r#"{{"id": {}, "name": "user_{}""#, id, user),
+        // Unquoted key
+        2 => format!(r#"{{"id": {}, name: "user_{}"}}"#, id, user),
+        // Unquoted literal value
+        3 => format!(r#"{{"id": {}, "name": user_{}}}"#, id, user),
+        // Invalid escape sequence
+        _ => format!(r#"{{"id": {}, "name": "bad\escape_{}"}}"#, id, user),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::validate_file_serde;
+
+    #[test]
+    fn test_corpus_is_reproducible() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = CorpusConfig {
+            files: 2,
+            lines_per_file: 200,
+            error_rate: 0.1,
+            seed: 7,
+        };
+
+        let first = generate_test_corpus(&temp_dir.path().join("first"), &config).unwrap();
+        let second = generate_test_corpus(&temp_dir.path().join("second"), &config).unwrap();
+        assert_eq!(first.len(), 2);
+
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(fs::read(a).unwrap(), fs::read(b).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_corpus_error_rate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let config = CorpusConfig {
+            files: 1,
+            lines_per_file: 50,
+            error_rate: 0.0,
+            ..Default::default()
+        };
+        let files = generate_test_corpus(&temp_dir.path().join("valid"), &config).unwrap();
+        assert!(validate_file_serde(&files[0]).unwrap().is_empty());
+
+        let config = CorpusConfig { error_rate: 1.0, ..config };
+        let files = generate_test_corpus(&temp_dir.path().join("invalid"), &config).unwrap();
+        assert_eq!(validate_file_serde(&files[0]).unwrap().len(), 50);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/// Prefix of the error raised for a repeated key, used to tell it apart from other parse errors
+const DUPLICATE_KEY_PREFIX: &str = "duplicate key";
+
+/// Returns an error message if any object in `line` repeats a key.
+///
+/// `serde_json::Value` silently keeps the last of several values for the same key, so this walks
+/// the line with a visitor that sees every key instead. Lines that fail to parse for any other
+/// reason are not reported here; that is the backend's job.
+pub(crate) fn find_duplicate_key(line: &str) -> Option<String> {
+    let e = serde_json::from_str::<NoDuplicateKeys>(line).err()?;
+
+    // Strip the position that serde_json appends to every error message
+    let message = e.to_string();
+    let position = format!(" at line {} column {}", e.line(), e.column());
+    let message = message.strip_suffix(&position).unwrap_or(&message);
+
+    message.starts_with(DUPLICATE_KEY_PREFIX).then(|| message.to_string())
+}
+
+/// A JSON value that fails to deserialize if any object in it repeats a key
+struct NoDuplicateKeys;
+
+impl<'de> Deserialize<'de> for NoDuplicateKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NoDuplicateKeysVisitor)
+    }
+}
+
+struct NoDuplicateKeysVisitor;
+
+impl<'de> Visitor<'de> for NoDuplicateKeysVisitor {
+    type Value = NoDuplicateKeys;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<NoDuplicateKeys>()?.is_some() {}
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut keys = HashSet::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            if keys.contains(&key) {
+                return Err(de::Error::custom(format!("{} '{}' in object", DUPLICATE_KEY_PREFIX, key)));
+            }
+            map.next_value::<NoDuplicateKeys>()?;
+            keys.insert(key);
+        }
+
+        Ok(NoDuplicateKeys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicate_key() {
+        assert_eq!(find_duplicate_key("{\"a\": 1, \"b\": 2}"), None);
+        assert_eq!(find_duplicate_key("{\"a\": 1, \"a\": 2}").as_deref(), Some("duplicate key 'a' in object"));
+
+        // Keys only clash within the same object
+        assert_eq!(find_duplicate_key("[{\"a\": 1}, {\"a\": 2}]"), None);
+        assert_eq!(find_duplicate_key("{\"a\": {\"a\": 1}}"), None);
+        assert_eq!(
+            find_duplicate_key("[1, null, {\"x\": [{\"k\": \"v\", \"k\": true}]}]").as_deref(),
+            Some("duplicate key 'k' in object")
+        );
+
+        // Escaped keys are compared after unescaping
+        assert!(find_duplicate_key("{\"\\u0061\": 1, \"a\": 2}").is_some());
+
+        // Other parse errors are left to the backend
+        assert_eq!(find_duplicate_key("{\"a\": }"), None);
+    }
+}
@@ -1,5 +1,7 @@
 use std::io;
 use std::path::PathBuf;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,25 +22,505 @@ pub enum NdJsonError {
     #[error("Failed to create output directory: {0}")]
     FailedToCreateOutputDir(String),
     
+    /// No longer returned; directory walks report `DirectoryTraversalError` with the path instead
+    #[deprecated(since = "0.2.0", note = "directory walks return DirectoryTraversalError")]
     #[error("File system error: {0}")]
-    Walkdir(#[from] walkdir::Error),
+    Walkdir(#[source] walkdir::Error),
+    
+    #[error("Failed to traverse {}: {source}", path.display())]
+    DirectoryTraversalError {
+        /// Entry that could not be read, or the directory being walked if walkdir doesn't know it
+        path: PathBuf,
+        source: walkdir::Error,
+    },
+    
+    #[error("File {} is not valid UTF-8 (invalid byte at offset {byte_offset})", path.display())]
+    UnsupportedEncoding {
+        path: PathBuf,
+        byte_offset: u64,
+    },
+    
+    #[error("File {} is {size} bytes, which exceeds the limit of {limit} bytes", path.display())]
+    FileTooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+    
+    #[error("Found {found} ND-JSON files, but at least {expected} were expected")]
+    TooFewFiles {
+        found: usize,
+        expected: usize,
+    },
+    
+    #[error("Validation cancelled after {} files", .0.total_files)]
+    Cancelled(ValidationSummary),
+    
+    #[error("Invalid JSON at line {} in file {}: {}", .0.line_number, .0.file_path.display(), .0.error)]
+    InvalidLine(Box<ValidationError>),
+    
+    #[error("Error rate of {:.1}% exceeds the threshold of {:.1}% after {files_processed} files", current_rate * 100.0, threshold * 100.0)]
+    ErrorRateExceeded {
+        current_rate: f64,
+        threshold: f64,
+        files_processed: usize,
+    },
+    
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
 }
 
 pub type Result<T> = std::result::Result<T, NdJsonError>;
 
-/// Represents a validation error in an ND-JSON file
-#[derive(Debug)]
+impl From<NdJsonError> for io::Error {
+    // Still matched so that existing values keep converting as before
+    #[allow(deprecated)]
+    fn from(err: NdJsonError) -> Self {
+        let kind = match &err {
+            NdJsonError::Walkdir(e) | NdJsonError::DirectoryTraversalError { source: e, .. } => {
+                e.io_error().map_or(io::ErrorKind::Other, io::Error::kind)
+            }
+            NdJsonError::JsonParse { .. }
+            | NdJsonError::UnsupportedEncoding { .. }
+            | NdJsonError::InvalidLine(_)
+            | NdJsonError::ErrorRateExceeded { .. }
+            | NdJsonError::Csv(_) => io::ErrorKind::InvalidData,
+            NdJsonError::NoFilesFound(_) | NdJsonError::TooFewFiles { .. } => io::ErrorKind::NotFound,
+            NdJsonError::FileTooLarge { .. } | NdJsonError::InvalidConfig(_) => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::Other,
+        };
+
+        match err {
+            // Hand back the original IO error rather than wrapping it
+            NdJsonError::Io(e) => e,
+            err => io::Error::new(kind, err),
+        }
+    }
+}
+
+/// Represents a validation error in an ND-JSON file.
+///
+/// With the default `serde` feature it serializes with `file_path` as a string, so paths must be
+/// valid UTF-8 to be serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ValidationError {
     pub file_path: PathBuf,
     pub line_number: usize,
     pub line_content: String,
     pub error: String,
+    /// SHA-256 hash of the line, set when `ValidatorConfig::compute_line_hashes` is enabled.
+    /// Identical invalid lines share a hash, which allows deduplicating errors across files.
+    pub line_hash: Option<[u8; 32]>,
+    /// 1-based column at which the parser gave up, as reported by the backend. serde_json and
+    /// sonic-rs do not always stop at the same position for the same line.
+    pub column_number: Option<usize>,
+    /// 0-based byte offset into the line at which sonic-rs gave up. Only set by the sonic-rs
+    /// backend; unlike `column_number` it can be used to slice `line_content` directly.
+    pub sonic_error_offset: Option<usize>,
+}
+
+impl ValidationError {
+    /// Truncates `line_content` in place to at most `max_bytes` bytes.
+    ///
+    /// The cut is moved back to the nearest UTF-8 character boundary, so the result may be
+    /// slightly shorter than `max_bytes` but is always valid UTF-8.
+    pub fn truncate_line_content(&mut self, max_bytes: usize) {
+        if self.line_content.len() <= max_bytes {
+            return;
+        }
+
+        let mut end = max_bytes;
+        while !self.line_content.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.line_content.truncate(end);
+    }
+
+    /// Truncates the `line_content` of every error, e.g. before sending them over a size-limited transport
+    pub fn truncate_all(errors: &mut [ValidationError], max_bytes: usize) {
+        for error in errors {
+            error.truncate_line_content(max_bytes);
+        }
+    }
+}
+
+/// A problem with a file as a whole that doesn't make any single line invalid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationWarning {
+    pub file_path: PathBuf,
+    /// 1-based line the warning points at, or `None` if it is about the file as a whole
+    pub line_number: Option<usize>,
+    pub message: String,
+}
+
+impl ValidationWarning {
+    /// Turns the warning into an error, as done for `ValidatorConfig::warnings_as_errors`. Warnings
+    /// about the file as a whole are reported on line 0, and `line_content` is left empty.
+    pub fn into_error(self) -> ValidationError {
+        ValidationError {
+            file_path: self.file_path,
+            line_number: self.line_number.unwrap_or(0),
+            line_content: String::new(),
+            error: self.message,
+            line_hash: None,
+            column_number: None,
+            sonic_error_offset: None,
+        }
+    }
 }
 
 /// Summary of validation results
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ValidationSummary {
     pub total_files: usize,
     pub files_with_errors: usize,
     pub total_errors: usize,
+    /// Whether only part of each file was validated, because of `ValidatorConfig::first_line_only`
+    /// or `ValidatorConfig::line_range`
+    pub partial_scan: bool,
+    /// Whether a file reached `ValidatorConfig::max_errors_per_file` (or its first error with
+    /// `ValidatorConfig::fail_fast`) or the batch reached `ValidatorConfig::max_total_errors`, so
+    /// there may be more errors than were reported
+    pub truncated: bool,
+}
+
+impl ValidationSummary {
+    /// Returns `true` if no invalid lines were found
+    pub fn is_clean(&self) -> bool {
+        self.total_errors == 0
+    }
+
+    /// Returns `true` if at least one invalid line was found
+    pub fn has_errors(&self) -> bool {
+        !self.is_clean()
+    }
+
+    /// Combines the summaries of two disjoint sets of files, e.g. from separate shards
+    pub fn merge(&self, other: &ValidationSummary) -> ValidationSummary {
+        ValidationSummary {
+            total_files: self.total_files + other.total_files,
+            files_with_errors: self.files_with_errors + other.files_with_errors,
+            total_errors: self.total_errors + other.total_errors,
+            partial_scan: self.partial_scan || other.partial_scan,
+            truncated: self.truncated || other.truncated,
+        }
+    }
+
+    /// How this summary differs from one of an earlier run, e.g. one stored by a previous CI job.
+    /// Positive values mean this run has more.
+    pub fn delta(&self, previous: &ValidationSummary) -> SummaryDelta {
+        let difference = |current: usize, previous: usize| current as isize - previous as isize;
+        SummaryDelta {
+            new_files_with_errors: difference(self.files_with_errors, previous.files_with_errors),
+            new_total_errors: difference(self.total_errors, previous.total_errors),
+        }
+    }
+
+    /// Renders `files` as an aligned plain-text table with `File`, `Lines`, `Errors` and `Valid%`
+    /// columns, followed by a total row for the whole summary.
+    ///
+    /// Paths longer than 40 characters keep their end and are prefixed with `...`.
+    pub fn to_table_string(&self, files: &[FileReport]) -> String {
+        let total_lines: usize = files.iter().map(|file| file.total_lines).sum();
+        let mut rows: Vec<[String; 4]> = files
+            .iter()
+            .map(|file| {
+                [
+                    truncate_path(&file.file_path.display().to_string()),
+                    file.total_lines.to_string(),
+                    file.error_count.to_string(),
+                    valid_percentage(file.total_lines, file.error_count),
+                ]
+            })
+            .collect();
+        rows.push([
+            format!("Total ({} files)", self.total_files),
+            total_lines.to_string(),
+            self.total_errors.to_string(),
+            valid_percentage(total_lines, self.total_errors),
+        ]);
+
+        let header = ["File", "Lines", "Errors", "Valid%"].map(String::from);
+        let mut widths = header.clone().map(|column| column.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |row: &[String; 4]| {
+            format!(
+                "| {:<w0$} | {:>w1$} | {:>w2$} | {:>w3$} |\n",
+                row[0], row[1], row[2], row[3],
+                w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]
+            )
+        };
+        let separator = format!(
+            "+{}+\n",
+            widths.iter().map(|width| "-".repeat(width + 2)).collect::<Vec<_>>().join("+")
+        );
+
+        let (total, file_rows) = rows.split_last().expect("total row is always present");
+        let mut table = separator.clone();
+        table.push_str(&format_row(&header));
+        table.push_str(&separator);
+        for row in file_rows {
+            table.push_str(&format_row(row));
+        }
+        if !file_rows.is_empty() {
+            table.push_str(&separator);
+        }
+        table.push_str(&format_row(total));
+        table.push_str(&separator);
+        table
+    }
+}
+
+/// Difference between two validation summaries, see [`ValidationSummary::delta`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SummaryDelta {
+    /// Change in the number of files with errors
+    pub new_files_with_errors: isize,
+    /// Change in the total number of errors, negative if errors were fixed
+    pub new_total_errors: isize,
+}
+
+impl SummaryDelta {
+    /// Returns `true` if the later run found more invalid lines than the earlier one
+    pub fn has_new_errors(&self) -> bool {
+        self.new_total_errors > 0
+    }
+}
+
+/// Line and error counts for a single validated file, as shown by [`ValidationSummary::to_table_string`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct FileReport {
+    pub file_path: PathBuf,
+    pub total_lines: usize,
+    pub error_count: usize,
+}
+
+const MAX_TABLE_PATH_CHARS: usize = 40;
+
+fn truncate_path(path: &str) -> String {
+    let char_count = path.chars().count();
+    if char_count <= MAX_TABLE_PATH_CHARS {
+        return path.to_string();
+    }
+    let tail: String = path.chars().skip(char_count - (MAX_TABLE_PATH_CHARS - 3)).collect();
+    format!("...{}", tail)
+}
+
+fn valid_percentage(total_lines: usize, error_count: usize) -> String {
+    if total_lines == 0 {
+        return "100.0%".to_string();
+    }
+    let valid_lines = total_lines.saturating_sub(error_count);
+    format!("{:.1}%", valid_lines as f64 * 100.0 / total_lines as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_with_content(line_content: &str) -> ValidationError {
+        ValidationError {
+            file_path: PathBuf::from("test.ndjson"),
+            line_number: 1,
+            line_content: line_content.to_string(),
+            error: "test error".to_string(),
+            line_hash: None,
+            column_number: None,
+            sonic_error_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_truncate_line_content() {
+        let mut error = error_with_content("{\"name\": \"Alice\"}");
+        error.truncate_line_content(8);
+        assert_eq!(error.line_content, "{\"name\":");
+
+        // Content shorter than the limit is left alone
+        error.truncate_line_content(100);
+        assert_eq!(error.line_content, "{\"name\":");
+    }
+
+    #[test]
+    fn test_truncate_line_content_respects_char_boundaries() {
+        // "é" is two bytes, so cutting at 2 bytes would split it
+        let mut error = error_with_content("aé");
+        error.truncate_line_content(2);
+        assert_eq!(error.line_content, "a");
+    }
+
+    #[test]
+    fn test_truncate_all() {
+        let mut errors = vec![error_with_content("abcdef"), error_with_content("xyz")];
+        ValidationError::truncate_all(&mut errors, 4);
+        assert_eq!(errors[0].line_content, "abcd");
+        assert_eq!(errors[1].line_content, "xyz");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_validation_error_serde_round_trip() {
+        let mut error = error_with_content("{\"a\": }");
+        error.line_hash = Some([7; 32]);
+        error.column_number = Some(7);
+
+        let json = serde_json::to_string(&error).unwrap();
+        assert!(json.contains("\"file_path\":\"test.ndjson\""));
+        let round_tripped: ValidationError = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, error);
+
+        // Equal errors collapse in a set
+        let errors: std::collections::HashSet<_> = [error.clone(), round_tripped, error_with_content("x")].into_iter().collect();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_validation_summary_serde_round_trip() {
+        let summary = ValidationSummary {
+            total_files: 3,
+            files_with_errors: 1,
+            total_errors: 2,
+            partial_scan: true,
+            truncated: false,
+        };
+        let round_tripped: ValidationSummary = serde_json::from_str(&serde_json::to_string(&summary).unwrap()).unwrap();
+        assert_eq!(round_tripped.total_errors, 2);
+        assert!(round_tripped.partial_scan);
+    }
+
+    #[test]
+    fn test_summary_delta() {
+        let summary = |total_files, files_with_errors, total_errors| ValidationSummary {
+            total_files,
+            files_with_errors,
+            total_errors,
+            partial_scan: false,
+            truncated: false,
+        };
+        let before = summary(10, 2, 5);
+
+        let delta = summary(12, 3, 9).delta(&before);
+        assert_eq!(
+            delta,
+            SummaryDelta {
+                new_files_with_errors: 1,
+                new_total_errors: 4,
+            }
+        );
+        assert!(delta.has_new_errors());
+
+        let delta = summary(10, 1, 1).delta(&before);
+        assert_eq!(delta.new_files_with_errors, -1);
+        assert_eq!(delta.new_total_errors, -4);
+        assert!(!delta.has_new_errors());
+
+        // Errors moving between files change only the file count
+        let delta = summary(10, 3, 5).delta(&before);
+        assert_eq!((delta.new_files_with_errors, delta.new_total_errors), (1, 0));
+        assert!(!delta.has_new_errors());
+    }
+
+    #[test]
+    fn test_summary_predicates() {
+        let summary = ValidationSummary {
+            total_files: 2,
+            files_with_errors: 0,
+            total_errors: 0,
+            partial_scan: false,
+            truncated: false,
+        };
+        assert!(summary.is_clean());
+        assert!(!summary.has_errors());
+
+        let summary = ValidationSummary {
+            total_files: 2,
+            files_with_errors: 1,
+            total_errors: 3,
+            partial_scan: false,
+            truncated: false,
+        };
+        assert!(!summary.is_clean());
+        assert!(summary.has_errors());
+
+        let merged = summary.merge(&ValidationSummary {
+            total_files: 1,
+            files_with_errors: 1,
+            total_errors: 2,
+            partial_scan: false,
+            truncated: false,
+        });
+        assert_eq!((merged.total_files, merged.files_with_errors, merged.total_errors), (3, 2, 5));
+    }
+
+    #[test]
+    fn test_to_table_string() {
+        let summary = ValidationSummary {
+            total_files: 2,
+            files_with_errors: 1,
+            total_errors: 1,
+            partial_scan: false,
+            truncated: false,
+        };
+        let files = [
+            FileReport {
+                file_path: PathBuf::from("a.ndjson"),
+                total_lines: 8,
+                error_count: 1,
+            },
+            FileReport {
+                file_path: PathBuf::from("data/very/deeply/nested/directory/of/exports/b.ndjson"),
+                total_lines: 2,
+                error_count: 0,
+            },
+        ];
+
+        let expected = "\
++------------------------------------------+-------+--------+--------+
+| File                                     | Lines | Errors | Valid% |
++------------------------------------------+-------+--------+--------+
+| a.ndjson                                 |     8 |      1 |  87.5% |
+| .../nested/directory/of/exports/b.ndjson |     2 |      0 | 100.0% |
++------------------------------------------+-------+--------+--------+
+| Total (2 files)                          |    10 |      1 |  90.0% |
++------------------------------------------+-------+--------+--------+
+";
+        assert_eq!(summary.to_table_string(&files), expected);
+    }
+
+    #[test]
+    fn test_into_io_error() {
+        let io_err: io::Error = NdJsonError::Io(io::Error::new(io::ErrorKind::PermissionDenied, "denied")).into();
+        assert_eq!(io_err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(io_err.to_string(), "denied");
+
+        let err = NdJsonError::NoFilesFound("data".to_string());
+        let message = err.to_string();
+        let io_err: io::Error = err.into();
+        assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(io_err.to_string(), message);
+
+        let io_err: io::Error = NdJsonError::UnsupportedEncoding {
+            path: PathBuf::from("test.ndjson"),
+            byte_offset: 3,
+        }
+        .into();
+        assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -0,0 +1,172 @@
+/// Returns an error message if a number in `line` cannot be represented exactly by the `f64` that
+/// serde_json reads it as.
+///
+/// Re-serializing the parsed `serde_json::Value` always gives back the same `f64`, so that cannot
+/// detect the loss. Instead this scans the number literals in the line, the same way
+/// `nesting_depth` scans brackets, and compares each one's decimal value with the shortest
+/// representation of the `f64` it parses to. Integers that fit in an `i64` or `u64` are exact and
+/// are skipped. The line must already have been validated.
+pub(crate) fn find_imprecise_float(line: &str) -> Option<String> {
+    number_literals(line).find_map(|literal| {
+        if literal.parse::<i64>().is_ok() || literal.parse::<u64>().is_ok() {
+            return None;
+        }
+        let value: f64 = literal.parse().ok()?;
+        let round_tripped = serde_json::to_string(&value).ok()?;
+        (Decimal::parse(literal) != Decimal::parse(&round_tripped)).then(|| {
+            format!("number {} loses precision as a float (reads as {})", literal, round_tripped)
+        })
+    })
+}
+
+/// Longest run of zeros `normalize_numbers` will write when spelling out an exponent. Literals
+/// like `1e400` are left alone rather than expanded into hundreds of digits.
+const MAX_EXPANDED_ZEROS: i64 = 64;
+
+/// Rewrites the number literals in `line` into plain decimal form: `1.0` becomes `1`, `1e3`
+/// becomes `1000` and `2.5E-3` becomes `0.0025`.
+///
+/// Like `find_imprecise_float` this works on the literals themselves rather than a parsed
+/// `serde_json::Value`, so the digits are kept exactly and everything else in the line, including
+/// key order and whitespace, is left as it was. The line must already have been validated.
+pub(crate) fn normalize_numbers(line: &str) -> String {
+    let mut normalized = String::with_capacity(line.len());
+    let mut copied_up_to = 0;
+    for literal in number_literals(line) {
+        let start = literal.as_ptr() as usize - line.as_ptr() as usize;
+        if let Some(decimal) = Decimal::parse(literal).to_plain_string() {
+            normalized.push_str(&line[copied_up_to..start]);
+            normalized.push_str(&decimal);
+            copied_up_to = start + literal.len();
+        }
+    }
+    normalized.push_str(&line[copied_up_to..]);
+    normalized
+}
+
+/// Number literals outside of strings, in the order they appear
+fn number_literals(line: &str) -> impl Iterator<Item = &str> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let byte = bytes[i];
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                i += 1;
+                continue;
+            }
+
+            match byte {
+                b'"' => in_string = true,
+                b'-' | b'0'..=b'9' => {
+                    let start = i;
+                    while i < bytes.len() && matches!(bytes[i], b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                        i += 1;
+                    }
+                    return Some(&line[start..i]);
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    })
+}
+
+/// Exact value of a decimal literal as `0.digits × 10^exponent`, with no leading or trailing zeros
+/// in `digits`, so equal values compare equal regardless of how they were written
+#[derive(Debug, PartialEq, Eq)]
+struct Decimal {
+    negative: bool,
+    digits: String,
+    exponent: i64,
+}
+
+impl Decimal {
+    fn parse(literal: &str) -> Self {
+        let (negative, unsigned) = match literal.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, literal),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            // An exponent too large for an i64 can't survive the trip through an f64 either
+            Some(pos) => (&unsigned[..pos], unsigned[pos + 1..].parse::<i64>().unwrap_or(i64::MAX)),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        let all_digits = format!("{}{}", integer, fraction);
+        let leading_zeros = all_digits.len() - all_digits.trim_start_matches('0').len();
+        let digits = all_digits.trim_matches('0').to_string();
+        if digits.is_empty() {
+            return Self { negative: false, digits, exponent: 0 };
+        }
+
+        Self {
+            negative,
+            digits,
+            exponent: exponent.saturating_add(integer.len() as i64 - leading_zeros as i64),
+        }
+    }
+
+    /// The value written without an exponent or trailing fractional zeros, or `None` if that
+    /// would need more than `MAX_EXPANDED_ZEROS` zeros
+    fn to_plain_string(&self) -> Option<String> {
+        if self.digits.is_empty() {
+            return Some("0".to_string());
+        }
+        let sign = if self.negative { "-" } else { "" };
+        let digit_count = self.digits.len() as i64;
+        if self.exponent <= 0 {
+            let zeros = self.exponent.checked_neg().filter(|zeros| *zeros <= MAX_EXPANDED_ZEROS)?;
+            Some(format!("{}0.{}{}", sign, "0".repeat(zeros as usize), self.digits))
+        } else if self.exponent >= digit_count {
+            let zeros = self.exponent - digit_count;
+            (zeros <= MAX_EXPANDED_ZEROS).then(|| format!("{}{}{}", sign, self.digits, "0".repeat(zeros as usize)))
+        } else {
+            let (integer, fraction) = self.digits.split_at(self.exponent as usize);
+            Some(format!("{}{}.{}", sign, integer, fraction))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_imprecise_float() {
+        // Different spellings of a value that an f64 holds exactly are fine
+        assert_eq!(find_imprecise_float(r#"{"a": 1.50, "b": 1e2, "c": -0.0, "d": 0.1, "e": 12345678901234567890}"#), None);
+        // Digits inside strings are ignored
+        assert_eq!(find_imprecise_float(r#"{"0.10000000000000000001": "3.14159265358979323846"}"#), None);
+
+        assert_eq!(
+            find_imprecise_float(r#"{"pi": 3.14159265358979323846}"#),
+            Some("number 3.14159265358979323846 loses precision as a float (reads as 3.141592653589793)".to_string())
+        );
+        assert!(find_imprecise_float("[123456789012345678901234567890]").is_some());
+        assert!(find_imprecise_float("[9007199254740993.0]").is_some());
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        assert_eq!(
+            normalize_numbers(r#"{"b": 1.0, "a": [1e3, -2.50, 2.5E-3, 0.0, -0.0, 12], "s": "1.0"}"#),
+            r#"{"b": 1, "a": [1000, -2.5, 0.0025, 0, 0, 12], "s": "1.0"}"#
+        );
+        // Digits are kept exactly, even where an f64 would round them
+        assert_eq!(normalize_numbers("[9007199254740993.0, 1.5e30]"), "[9007199254740993, 1500000000000000000000000000000]");
+        // Huge exponents are not spelled out
+        assert_eq!(normalize_numbers("[1e400, 1e-400]"), "[1e400, 1e-400]");
+    }
+}
@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::config::ValidatorConfig;
+use crate::error::{NdJsonError, Result};
+use crate::validator::{check_line, Backend, Lines};
+
+/// Reads the valid JSON lines of a file, validating them with serde_json.
+///
+/// Blank lines are skipped. Invalid lines are skipped as well, unless `strict` is true, in which
+/// case they are yielded as `NdJsonError::InvalidLine` so the caller can decide what to do.
+pub fn read_valid_lines_serde(file_path: &Path, strict: bool) -> Result<impl Iterator<Item = Result<String>>> {
+    let file = File::open(file_path)?;
+    let path = file_path.to_path_buf();
+    let config = ValidatorConfig::default();
+
+    let lines = Lines::new(BufReader::new(file), file_path).filter_map(move |line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if line.content.trim().is_empty() {
+            return None;
+        }
+
+        match check_line(&line.content, line.number, &path, &config, Backend::Serde) {
+            None => Some(Ok(line.content)),
+            Some(error) if strict => Some(Err(NdJsonError::InvalidLine(Box::new(error)))),
+            Some(_) => None,
+        }
+    });
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_valid_lines_skips_invalid_lines() {
+        let lines = read_valid_lines_serde(Path::new("tests/invalid2.ndjson"), false)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(lines, vec!["{\"name\": \"Alice\", \"age\": 30}", "{\"name\": \"Jack\", \"age\": 35}"]);
+    }
+
+    #[test]
+    fn test_read_valid_lines_strict() {
+        let results: Vec<_> = read_valid_lines_serde(Path::new("tests/invalid1.ndjson"), true)
+            .unwrap()
+            .collect();
+
+        assert_eq!(results.len(), 3);
+        match &results[0] {
+            Err(NdJsonError::InvalidLine(error)) => assert_eq!(error.line_number, 1),
+            other => panic!("expected an invalid line error, got {:?}", other),
+        }
+        assert!(results[1..].iter().all(|result| result.is_ok()));
+    }
+}
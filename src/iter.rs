@@ -0,0 +1,146 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::config::ValidatorConfig;
+use crate::error::{Result, ValidationError};
+use crate::validator::{Backend, LineChecker, Lines};
+
+/// Validates ND-JSON lazily, yielding one `ValidationError` per invalid line.
+///
+/// Valid lines are skipped without being returned, and nothing is read beyond the line that
+/// produced the current error, so callers can stop early without reading the rest of the input.
+/// With `ValidatorConfig::line_range`, nothing is read past the end of the range. After an I/O
+/// error the iterator is exhausted.
+pub struct NdjsonValidatorIter<R: BufRead> {
+    lines: Lines<R>,
+    file_path: PathBuf,
+    config: ValidatorConfig,
+    checker: LineChecker,
+    done: bool,
+}
+
+impl<R: BufRead> NdjsonValidatorIter<R> {
+    /// Creates an iterator over the errors in `reader`, reported against `label`
+    pub fn new(reader: R, label: &str) -> Self {
+        Self::with_config(reader, label, ValidatorConfig::default())
+    }
+
+    /// Creates an iterator that honours the line handling options in `config`
+    pub fn with_config(reader: R, label: &str, config: ValidatorConfig) -> Self {
+        Self::with_path(reader, PathBuf::from(label), config)
+    }
+
+    /// Like `with_config`, but reports errors against a path that need not be valid UTF-8
+    pub(crate) fn with_path(reader: R, file_path: PathBuf, config: ValidatorConfig) -> Self {
+        Self {
+            lines: Lines::new(reader, &file_path).with_latin1_fallback(config.encoding_fallback),
+            file_path,
+            checker: LineChecker::new(&config),
+            config,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonValidatorIter<R> {
+    type Item = Result<ValidationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            if let Some(range) = &self.config.line_range {
+                if line.number >= range.end {
+                    self.done = true;
+                    return None;
+                }
+                if line.number < range.start {
+                    // Still needed to spot a schema header that comes before the range
+                    self.checker.is_schema_header(&line.content, line.number, &self.config);
+                    continue;
+                }
+            }
+            if let Some(error) = self.checker.check(&line.content, line.number, &self.file_path, &self.config, Backend::Serde) {
+                return Some(Ok(error));
+            }
+        }
+        None
+    }
+}
+
+/// Opens a file for lazy validation with serde_json, see `NdjsonValidatorIter`
+pub fn iter_file_serde(path: &Path) -> Result<NdjsonValidatorIter<BufReader<File>>> {
+    let file = File::open(path)?;
+    Ok(NdjsonValidatorIter::with_path(BufReader::new(file), path.to_path_buf(), ValidatorConfig::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_file() {
+        let errors = iter_file_serde(Path::new("tests/invalid2.ndjson"))
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let expected = crate::validate_file_serde(Path::new("tests/invalid2.ndjson")).unwrap();
+        assert_eq!(
+            errors.iter().map(|e| e.line_number).collect::<Vec<_>>(),
+            expected.iter().map(|e| e.line_number).collect::<Vec<_>>()
+        );
+        assert_eq!(errors[0].file_path, Path::new("tests/invalid2.ndjson"));
+    }
+
+    #[test]
+    fn test_stops_reading_at_the_current_error() {
+        let content = "{\"a\": 1}\n{\"a\": }\n{\"a\": 3}\n{\"a\": }\n";
+        let mut reader = content.as_bytes();
+        let mut iter = NdjsonValidatorIter::new(&mut reader, "stream");
+
+        assert_eq!(iter.next().unwrap().unwrap().line_number, 2);
+        drop(iter);
+        // Only the first two lines have been consumed
+        assert_eq!(reader, "{\"a\": 3}\n{\"a\": }\n".as_bytes());
+    }
+
+    #[test]
+    fn test_line_range() {
+        let content = "{\"a\": }\n{\"a\": }\n{\"a\": }\n{\"a\": }\n{\"b\": }\n";
+        let config = ValidatorConfig {
+            line_range: Some(2..4),
+            ..Default::default()
+        };
+        let mut reader = content.as_bytes();
+        let iter = NdjsonValidatorIter::with_config(&mut reader, "range", config);
+
+        let lines = iter.map(|error| error.unwrap().line_number).collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 3]);
+        // Reading stopped at the first line past the range
+        assert_eq!(reader, "{\"b\": }\n".as_bytes());
+    }
+
+    #[test]
+    fn test_io_error_ends_iteration() {
+        let errors = iter_file_serde(Path::new("tests/fixtures/latin1.ndjson")).unwrap().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_err());
+
+        let config = ValidatorConfig {
+            encoding_fallback: true,
+            ..Default::default()
+        };
+        let file = File::open("tests/fixtures/latin1.ndjson").unwrap();
+        let mut iter = NdjsonValidatorIter::with_config(BufReader::new(file), "latin1", config);
+        assert!(iter.all(|error| error.is_ok()));
+    }
+}
@@ -1,18 +1,98 @@
+mod analyze;
+#[cfg(feature = "async")]
+pub mod async_validator;
 mod cleaner;
+mod compare;
 mod config;
+pub mod convert;
+pub mod corpus;
+mod duplicate_keys;
 mod error;
+mod float_precision;
+pub mod io;
+mod iter;
 mod processor;
+mod repair;
+mod report;
+mod shard;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "http-server")]
+pub mod server;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod streaming;
 mod validator;
 
 // Re-export public API
-pub use config::ValidatorConfig;
-pub use error::{NdJsonError, Result, ValidationError, ValidationSummary};
+pub use analyze::{
+    check_type_consistency, field_frequency_analysis, infer_schema, line_size_stats, type_summary,
+    validate_typed_shape, FieldStats, InferredSchema, JsonShape, JsonType, LineSizeStats,
+    TypeConsistencyReport,
+};
+pub use cleaner::{clean_content_serde, clean_file_diff};
+pub use compare::{compare_backends, BackendDisagreement, ComparisonResult};
+pub use config::{
+    FileHook, PostFileHook, PreFileHook, ValidatorConfig, ValidatorConfigBuilder,
+    ValidatorConfigOverlay,
+};
+pub use error::{
+    FileReport, NdJsonError, Result, SummaryDelta, ValidationError, ValidationSummary,
+    ValidationWarning,
+};
+pub use iter::{iter_file_serde, NdjsonValidatorIter};
+pub use repair::{repair_file_serde, FixType, RepairAction, RepairReport};
+pub use report::{to_html, OutputFormat, Report};
+pub use shard::ShardedValidator;
 pub use processor::{
-    process_file_serde, validate_directory_with_summary_serde, 
-    validate_files_serde, validate_files_with_summary_serde,
+    process_file_serde, validate_and_stream_valid_serde, validate_directory_leveled_serde,
+    validate_directory_with_summary_serde, validate_files_cancellable_serde,
+    validate_files_partitioned_serde, validate_files_serde, validate_files_with_summary_serde,
+    validate_files_to_ndjson_writer_serde, validate_string_batch_serde, validate_stdin_serde,
+    validate_tarball_serde, STDIN_LABEL, SUMMARY_FILE_NAME,
     process_file_sonic, validate_files_sonic, validate_files_with_summary_sonic,
     validate_directory_with_summary_sonic
 };
-pub use validator::{validate_file_serde, validate_file_sonic};
+pub use streaming::{validate_ndjson_bytes_streaming, StreamingValidator};
+pub use validator::{
+    is_valid_serde, validate_file_serde, validate_file_with_config_serde,
+    validate_file_with_index_serde, validate_file_with_warnings_serde, validate_reader_serde,
+    validate_str_serde, validate_typed_serde,
+    is_valid_sonic, validate_file_sonic, validate_file_with_config_sonic, validate_reader_sonic,
+    validate_str_sonic
+};
+
+/// Deprecated names from before every entry point carried a `_serde` or `_sonic` suffix.
+///
+/// Re-exports can't be deprecated, so these are thin wrappers that use serde_json, as the
+/// unsuffixed functions did.
+pub mod compat {
+    use std::path::{Path, PathBuf};
+
+    use crate::{Report, Result, ValidationError, ValidatorConfig};
+
+    #[deprecated(since = "0.2.0", note = "use validate_file_serde instead")]
+    pub fn validate_file(file_path: &Path) -> Result<Vec<ValidationError>> {
+        crate::validate_file_serde(file_path)
+    }
+
+    #[deprecated(since = "0.2.0", note = "use validate_files_serde instead")]
+    pub fn validate_files(files: &[PathBuf], config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+        crate::validate_files_serde(files, config)
+    }
+
+    #[deprecated(since = "0.2.0", note = "use process_file_serde instead")]
+    pub fn process_file(file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+        crate::process_file_serde(file_path, config)
+    }
 
+    #[deprecated(since = "0.2.0", note = "use validate_files_with_summary_serde instead")]
+    pub fn validate_files_with_summary(files: &[PathBuf], config: &ValidatorConfig) -> Result<Report> {
+        crate::validate_files_with_summary_serde(files, config)
+    }
 
+    #[deprecated(since = "0.2.0", note = "use validate_directory_with_summary_serde instead")]
+    pub fn validate_directory_with_summary(dir_path: &Path, config: &ValidatorConfig) -> Result<Report> {
+        crate::validate_directory_with_summary_serde(dir_path, config)
+    }
+}
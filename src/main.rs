@@ -3,24 +3,56 @@ use clap::Parser;
 
 mod cli;
 mod commands;
+mod output;
 
 use cli::{Cli, Commands};
-use commands::{handle_validate_dir, handle_validate_file, handle_validate_files};
+use commands::{
+    handle_analyze, handle_benchmark, handle_convert_from_csv, handle_generate_fixtures, handle_inspect, handle_validate_dir, handle_validate_file, handle_validate_files, handle_validate_pipe,
+};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::ValidateFile { file_path, clean, output_dir } => {
-            handle_validate_file(file_path, *clean, output_dir)
+        Commands::ValidateFile { file_path, clean, output_dir, stats, output_format } => {
+            handle_validate_file(file_path, *clean, output_dir, *stats, *output_format)
         },
         
-        Commands::ValidateFiles { file_paths, clean, output_dir } => {
-            handle_validate_files(file_paths, *clean, output_dir)
+        Commands::ValidateFiles { file_paths, clean, output_dir, output_format, report_file, first_line_only } => {
+            handle_validate_files(file_paths, *clean, output_dir, *output_format, report_file, *first_line_only)
         },
         
-        Commands::ValidateDir { dir_path, clean, output_dir } => {
-            handle_validate_dir(dir_path, *clean, output_dir)
+        Commands::ValidateDir { dir_path, clean, output_dir, output_format, report_file, first_line_only } => {
+            handle_validate_dir(dir_path, *clean, output_dir, *output_format, report_file, *first_line_only)
+        },
+
+        Commands::ValidatePipe { clean, output_dir } => {
+            handle_validate_pipe(*clean, output_dir)
+        },
+
+        Commands::Analyze { file_path } => {
+            handle_analyze(file_path)
+        },
+
+        Commands::Inspect { file_path, output_format } => {
+            handle_inspect(file_path, *output_format)
+        },
+
+        Commands::Benchmark { file_path } => {
+            handle_benchmark(file_path)
+        },
+
+        Commands::GenerateFixtures { output_dir, files, lines, error_rate, seed } => {
+            handle_generate_fixtures(output_dir, *files, *lines, *error_rate, *seed)
+        },
+
+        Commands::ConvertFromCsv { input, output, delimiter, no_header } => {
+            handle_convert_from_csv(input, output, *delimiter, !*no_header)
+        },
+
+        #[cfg(feature = "http-server")]
+        Commands::Server { port } => {
+            commands::handle_server(*port)
         },
     }
 }
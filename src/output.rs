@@ -0,0 +1,78 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
+
+use ndjson_validator::ValidationError;
+
+/// Longest line content shown per error before it is cut off with `...`
+pub const DEFAULT_MAX_LINE_CONTENT_CHARS: usize = 200;
+
+/// Prints detailed error information, truncating line content longer than `max_line_content_chars`
+pub fn print_errors(errors: &[ValidationError], max_line_content_chars: usize) {
+    // Failing to write to stdout is not worth aborting over
+    let _ = write_errors(&mut io::stdout().lock(), errors, max_line_content_chars);
+}
+
+/// Writes detailed error information to the given output
+pub fn write_errors(out: &mut impl Write, errors: &[ValidationError], max_line_content_chars: usize) -> io::Result<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+    
+    let max_errors_to_display = 10;
+    let display_count = std::cmp::min(errors.len(), max_errors_to_display);
+    
+    writeln!(out, "\nError Details (showing first {}/{}):", display_count, errors.len())?;
+    
+    for (i, error) in errors.iter().take(display_count).enumerate() {
+        writeln!(out, "{}. File: {}", i + 1, error.file_path.display())?;
+        writeln!(
+            out,
+            "   Line {}: {}",
+            error.line_number,
+            truncate_chars(&error.line_content, max_line_content_chars)
+        )?;
+        writeln!(out, "   Error: {}", error.error)?;
+        writeln!(out)?;
+    }
+    
+    if errors.len() > max_errors_to_display {
+        writeln!(out, "... and {} more errors", errors.len() - max_errors_to_display)?;
+    }
+
+    Ok(())
+}
+
+/// Prints information about the cleaning process
+pub fn print_cleaning_info(input_path: &Path, output_dir: &Path, error_count: usize) {
+    let file_name = input_path.file_name().unwrap_or_default();
+    let output_path = output_dir.join(file_name);
+    
+    if error_count == 0 {
+        println!("No errors to clean up.");
+    } else {
+        println!("Cleaned file written to: {}", output_path.display());
+        println!("Removed {} invalid lines", error_count);
+    }
+}
+
+/// Shortens `text` to at most `max_chars` characters, marking the cut with `...`
+pub fn truncate_chars(text: &str, max_chars: usize) -> Cow<'_, str> {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => Cow::Owned(format!("{}...", &text[..end])),
+        None => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_chars("{\"name\": \"Zoë\"}", 11), "{\"name\": \"Z...");
+        assert_eq!(truncate_chars("ééé", 2), "éé...");
+    }
+}
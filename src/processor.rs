@@ -1,31 +1,160 @@
-use std::collections::HashSet;
-use std::fs;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use flate2::read::GzDecoder;
 use rayon::prelude::*;
-use walkdir::WalkDir;
+use serde_json::{json, Value};
+use tar::Archive;
+use walkdir::{DirEntry, WalkDir};
 
-use crate::cleaner::clean_file;
+use crate::cleaner::{check_full_scan, clean_file, clean_reader};
 use crate::config::ValidatorConfig;
 use crate::error::{NdJsonError, Result, ValidationError, ValidationSummary};
-use crate::validator::{validate_file_serde, validate_file_sonic};
+use crate::report::{error_json, summary_json, Report};
+#[cfg(feature = "sqlite")]
+use crate::sqlite::write_error_log;
+use crate::validator::{find_warnings, for_each_error, has_warning_checks, promote_warnings, scan_reader, validate_reader, validate_str_serde, Backend, LineChecker, Lines, Scan};
+
+/// Synthetic file path used in errors for input read from standard input
+pub const STDIN_LABEL: &str = "<stdin>";
+
+/// Name of the file written to the output directory when `ValidatorConfig::write_summary_file` is set
+pub const SUMMARY_FILE_NAME: &str = "_validation_summary.json";
+
+/// Version of the summary file layout. Bump it whenever a field is renamed, removed or changes meaning.
+const SUMMARY_FILE_SCHEMA_VERSION: u32 = 1;
+
 
 /// Validates and optionally cleans a single ND-JSON file
 pub fn process_file_serde(file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
-    let errors = validate_file_serde(file_path)?;
+    process_file(file_path, config, Backend::Serde)
+}
+
+/// Validates ND-JSON read from standard input
+///
+/// Errors are reported against the synthetic path `<stdin>`. When cleaning is enabled the valid
+/// lines are written to `stdin.ndjson` in the output directory, or to standard output if no
+/// output directory is configured. Like cleaning a file, this needs the whole input, so
+/// `ValidatorConfig::first_line_only` and `ValidatorConfig::line_range` are then rejected with
+/// `NdJsonError::InvalidConfig`.
+pub fn validate_stdin_serde(config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+    let stdin = io::stdin();
+    validate_stdin(BufReader::new(stdin.lock()), config)
+}
+
+/// Validates `reader` in place of standard input, see `validate_stdin_serde`
+fn validate_stdin(reader: impl BufRead, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+    if !config.clean_files {
+        return validate_reader(reader, Path::new(STDIN_LABEL), config, Backend::Serde);
+    }
+    check_full_scan(config)?;
+
+    let output_path = match config.output_dir.as_ref().filter(|_| config.clean_files) {
+        Some(output_dir) => {
+            fs::create_dir_all(output_dir)
+                .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+            Some(output_dir.join("stdin.ndjson"))
+        }
+        None => None,
+    };
+
+    let mut writer: Box<dyn Write> = match &output_path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let streamed = stream_valid_lines(reader, Path::new(STDIN_LABEL), &mut writer, config)?;
+
+    // Mirror `clean_file`: an output file without any valid lines is removed
+    if let Some(path) = output_path {
+        if streamed.lines_written == 0 {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(streamed.errors)
+}
 
-    if config.clean_files && config.output_dir.is_some() {
-        let output_dir = config.output_dir.as_ref().unwrap();
-        fs::create_dir_all(output_dir)
-            .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+/// Validates a single ND-JSON file and writes its valid lines to `output` in the same pass, without
+/// holding them in memory. This is the single-pass alternative to validating and then cleaning.
+///
+/// Returns the number of valid lines written, including blank and skipped lines that are passed
+/// through, together with the errors. With `ValidatorConfig::invalid_line_replacement`, invalid
+/// lines are replaced in the output rather than dropped. As when cleaning, every invalid line is
+/// left out of the output but `ValidatorConfig::max_errors_per_file` limits the errors returned,
+/// and `ValidatorConfig::first_line_only` and `ValidatorConfig::line_range` are rejected with
+/// `NdJsonError::InvalidConfig`. With `ValidatorConfig::fail_fast`, reading and writing stop at
+/// the first invalid line.
+pub fn validate_and_stream_valid_serde(
+    input: &Path,
+    output: &mut impl Write,
+    config: &ValidatorConfig,
+) -> Result<(usize, Vec<ValidationError>)> {
+    check_full_scan(config)?;
+    let file = File::open(input)?;
+    let streamed = stream_valid_lines(BufReader::new(file), input, output, config)?;
+    Ok((streamed.valid_lines, streamed.errors))
+}
+
+/// Result of `stream_valid_lines`
+struct Streamed {
+    valid_lines: usize,
+    lines_written: usize,
+    errors: Vec<ValidationError>,
+}
 
-        let relative_path = file_path.file_name().unwrap_or_default();
-        let output_path = output_dir.join(relative_path);
+/// Validates `reader` line by line, writing the valid lines (and any `invalid_line_replacement`)
+/// to `output` as it goes
+fn stream_valid_lines<W: Write + ?Sized>(
+    reader: impl BufRead,
+    file_path: &Path,
+    output: &mut W,
+    config: &ValidatorConfig,
+) -> Result<Streamed> {
+    let mut streamed = Streamed {
+        valid_lines: 0,
+        lines_written: 0,
+        errors: Vec::new(),
+    };
+    let mut checker = LineChecker::new(config);
+    let error_limit = config.error_limit_per_file();
+
+    for line in Lines::new(reader, file_path).with_latin1_fallback(config.encoding_fallback) {
+        let mut line = line?;
+
+        let output_line = match checker.check(&line.content, line.number, file_path, config, Backend::Serde) {
+            Some(error) => {
+                // Cleaning needs every invalid line, so the per-file limit only applies to what is reported
+                if error_limit.is_none_or(|limit| streamed.errors.len() < limit) {
+                    streamed.errors.push(error);
+                }
+                if config.fail_fast {
+                    break;
+                }
+                config.invalid_line_replacement.as_deref()
+            }
+            None => {
+                streamed.valid_lines += 1;
+                if config.normalize_line_endings {
+                    line.content.retain(|c| c != '\r');
+                }
+                Some(line.content.as_str())
+            }
+        };
 
-        clean_file(file_path, &output_path, &errors)?;
+        if let Some(output_line) = output_line {
+            writeln!(output, "{}", output_line)?;
+            streamed.lines_written += 1;
+        }
     }
 
-    Ok(errors)
+    output.flush()?;
+    Ok(streamed)
 }
 
 /// Validates a list of ND-JSON files
@@ -33,163 +162,738 @@ pub fn validate_files_serde(
     files: &[PathBuf],
     config: &ValidatorConfig,
 ) -> Result<Vec<ValidationError>> {
-    let results = files
-        .par_iter()
-        .map(|file_path| process_file_serde(file_path, config))
-        .collect::<Vec<Result<Vec<ValidationError>>>>();
+    validate_files(files, config, Backend::Serde, None).map(|batch| batch.errors)
+}
 
-    // Flatten results and collect errors
-    let mut all_errors = Vec::new();
-    for result in results {
-        match result {
-            Ok(errors) => all_errors.extend(errors),
-            Err(e) => return Err(e),
-        }
+/// Validates a list of ND-JSON files one after another, writing each error to `output` as a line
+/// of ND-JSON as soon as it is found, so errors are never buffered in memory.
+///
+/// Every line is a JSON object with these fields, the same as in `OutputFormat::Ndjson` reports:
+///
+/// | Field | Type | Description |
+/// |-------|------|-------------|
+/// | `file_path` | string | Path of the file containing the invalid line |
+/// | `line_number` | integer | 1-based number of the invalid line |
+/// | `line_content` | string | The invalid line, or its hash with `ValidatorConfig::hash_line_content` |
+/// | `error` | string | Why the line is invalid |
+///
+/// Fields may be added in later versions, but existing ones will not change. Files are not cleaned.
+pub fn validate_files_to_ndjson_writer_serde(
+    paths: &[PathBuf],
+    config: &ValidatorConfig,
+    output: &mut impl Write,
+) -> Result<()> {
+    for path in paths {
+        let file = File::open(path)?;
+        for_each_error(BufReader::new(file), path, config, Backend::Serde, config.error_limit_per_file(), |error| {
+            writeln!(output, "{}", error_json(&error))?;
+            Ok(())
+        })?;
     }
 
-    Ok(all_errors)
+    output.flush()?;
+    Ok(())
 }
 
-/// Validates multiple ND-JSON files and returns a summary along with detailed errors
+/// Validates in-memory ND-JSON in parallel, returning the errors for each `(label, content)` pair
+/// in input order. Errors are reported against the label.
+pub fn validate_string_batch_serde(inputs: Vec<(String, String)>) -> Vec<(String, Vec<ValidationError>)> {
+    inputs
+        .into_par_iter()
+        .map(|(label, content)| {
+            // Reading from a `&str` can neither fail nor produce invalid UTF-8
+            let errors = validate_str_serde(&content, &label).expect("validating an in-memory string cannot fail");
+            (label, errors)
+        })
+        .collect()
+}
+
+/// Validates a list of ND-JSON files, stopping early once `cancelled` is set.
+///
+/// Each worker checks the flag before starting on a file, so files already in progress are
+/// finished. If any file was skipped, `NdJsonError::Cancelled` is returned with a summary of the
+/// files that were processed. Otherwise this behaves exactly like `validate_files_serde`.
+pub fn validate_files_cancellable_serde(
+    files: &[PathBuf],
+    config: &ValidatorConfig,
+    cancelled: Arc<AtomicBool>,
+) -> Result<Vec<ValidationError>> {
+    validate_files(files, config, Backend::Serde, Some(&cancelled)).map(|batch| batch.errors)
+}
+
+/// Validates multiple ND-JSON files and returns a report with a summary and detailed errors
 pub fn validate_files_with_summary_serde(
     files: &[PathBuf],
     config: &ValidatorConfig,
-) -> Result<(ValidationSummary, Vec<ValidationError>)> {
-    let errors = validate_files_serde(files, config)?;
-
-    // Count unique files with errors
-    let files_with_errors = errors
-        .iter()
-        .map(|e| &e.file_path)
-        .collect::<HashSet<_>>()
-        .len();
-
-    let summary = ValidationSummary {
-        total_files: files.len(),
-        files_with_errors,
-        total_errors: errors.len(),
+) -> Result<Report> {
+    validate_files_with_summary(files, config, Backend::Serde)
+}
+
+/// Validates a list of ND-JSON files grouped by the value of `config.partition_by_field`,
+/// returning a report per group.
+///
+/// Each file is assigned to a group by the field's value in its first valid line; files where the
+/// field is missing end up in the `Value::Null` group. Groups are validated one after another, and
+/// the files within a group in parallel.
+pub fn validate_files_partitioned_serde(
+    files: &[PathBuf],
+    config: &ValidatorConfig,
+) -> Result<HashMap<Value, Report>> {
+    let Some(field) = &config.partition_by_field else {
+        return Err(NdJsonError::InvalidConfig(
+            "partition_by_field must be set to validate files by partition".to_string(),
+        ));
     };
 
-    Ok((summary, errors))
+    let mut partitions: HashMap<Value, Vec<PathBuf>> = HashMap::new();
+    for file_path in files {
+        let key = partition_key(file_path, field, config)?;
+        partitions.entry(key).or_default().push(file_path.clone());
+    }
+
+    partitions
+        .into_iter()
+        .map(|(key, files)| Ok((key, validate_files_with_summary(&files, config, Backend::Serde)?)))
+        .collect()
 }
 
-/// Validates all ND-JSON files in a directory and returns a summary along with detailed errors
+/// Validates all ND-JSON files in a directory and returns a report with a summary and detailed errors
 pub fn validate_directory_with_summary_serde(
     dir_path: &Path,
     config: &ValidatorConfig,
-) -> Result<(ValidationSummary, Vec<ValidationError>)> {
-    let mut file_paths = Vec::new();
+) -> Result<Report> {
+    validate_directory_with_summary(dir_path, config, Backend::Serde)
+}
 
-    // Find all NDJSON files in the directory
-    for entry_result in WalkDir::new(dir_path).max_depth(1).into_iter() {
-        let entry = entry_result?;
+/// Validates the ND-JSON files under `root` down to `max_depth` and returns a summary per depth
+/// level, to show whether errors are concentrated at a particular level of the tree.
+///
+/// Level 0 holds the files directly inside `root`, level 1 the files in its subdirectories and so
+/// on. Every level from 0 to `max_depth` is present in the result, in order, even when it has no
+/// files.
+pub fn validate_directory_leveled_serde(
+    root: &Path,
+    max_depth: usize,
+    config: &ValidatorConfig,
+) -> Result<Vec<(usize, ValidationSummary)>> {
+    let mut levels = vec![Vec::new(); max_depth + 1];
+
+    let walker = WalkDir::new(root).min_depth(1).max_depth(max_depth + 1);
+    for entry_result in walker.into_iter().filter_entry(|entry| !is_skipped_hidden(entry, config)) {
+        let entry = entry_result.map_err(|source| NdJsonError::DirectoryTraversalError {
+            path: source.path().unwrap_or(root).to_path_buf(),
+            source,
+        })?;
         let path = entry.path();
-        if path.is_file()
-            && (path
-                .extension()
-                .map_or(false, |ext| ext == "ndjson" || ext == "jsonl")
-                || path.to_string_lossy().contains(".nd.json"))
-        {
-            file_paths.push(path.to_path_buf());
+        if is_candidate_file(path, config) && is_ndjson_path(path) {
+            levels[entry.depth() - 1].push(path.to_path_buf());
         }
     }
 
-    if file_paths.is_empty() {
-        return Err(NdJsonError::NoFilesFound(dir_path.display().to_string()));
+    levels
+        .into_iter()
+        .enumerate()
+        .map(|(depth, files)| Ok((depth, validate_files_with_summary(&files, config, Backend::Serde)?.summary)))
+        .collect()
+}
+
+/// Validates all ND-JSON files inside a `.tar.gz` archive without extracting it to disk.
+///
+/// Errors are reported against `<archive_path>/<entry path>`. When cleaning is enabled, cleaned
+/// entries are written to the output directory under their file name.
+pub fn validate_tarball_serde(
+    archive_path: &Path,
+    config: &ValidatorConfig,
+) -> Result<Report> {
+    let file = File::open(archive_path)?;
+    let mut archive = Archive::new(GzDecoder::new(BufReader::new(file)));
+
+    let mut total_files = 0;
+    let mut all_errors = Vec::new();
+    let mut all_warnings = Vec::new();
+    let mut truncated = false;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        if !entry.header().entry_type().is_file() || !is_ndjson_path(&entry_path) {
+            continue;
+        }
+
+        // Entries can only be read once, so buffer them for the cleaning pass
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+
+        let label = archive_path.join(&entry_path);
+        let scan = scan_reader(content.as_slice(), &label, config, Backend::Serde, config.error_limit_per_file())?;
+        let mut errors = scan.errors;
+        truncated |= scan.truncated;
+        let mut warnings = find_warnings(content.as_slice(), &label, config)?;
+
+        if let Some(output_dir) = config.output_dir.as_ref().filter(|_| config.clean_files) {
+            fs::create_dir_all(output_dir)
+                .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+
+            let output_path = output_dir.join(entry_path.file_name().unwrap_or_default());
+            clean_reader(content.as_slice(), &output_path, &errors, config)?;
+        }
+
+        // Only after cleaning, since the lines warned about are valid and must be kept
+        promote_warnings(&mut errors, &mut warnings, config);
+        all_warnings.extend(warnings);
+
+        total_files += 1;
+        all_errors.extend(errors);
+    }
+
+    if total_files == 0 {
+        return Err(NdJsonError::NoFilesFound(archive_path.display().to_string()));
     }
 
-    validate_files_with_summary_serde(&file_paths, config)
+    Ok(Report {
+        summary: summarize(total_files, &all_errors, truncated, config),
+        errors: all_errors,
+        warnings: all_warnings,
+        format: config.output_format,
+    })
 }
 
 /// Validates and optionally cleans a single ND-JSON file using sonic-rs
 pub fn process_file_sonic(file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
-    let errors = validate_file_sonic(file_path)?;
+    process_file(file_path, config, Backend::Sonic)
+}
+
+/// Validates a list of ND-JSON files using sonic-rs
+pub fn validate_files_sonic(
+    files: &[PathBuf],
+    config: &ValidatorConfig,
+) -> Result<Vec<ValidationError>> {
+    validate_files(files, config, Backend::Sonic, None).map(|batch| batch.errors)
+}
+
+/// Validates multiple ND-JSON files using sonic-rs and returns a report with a summary and detailed errors
+pub fn validate_files_with_summary_sonic(
+    files: &[PathBuf],
+    config: &ValidatorConfig,
+) -> Result<Report> {
+    validate_files_with_summary(files, config, Backend::Sonic)
+}
 
-    if config.clean_files && config.output_dir.is_some() {
-        let output_dir = config.output_dir.as_ref().unwrap();
-        fs::create_dir_all(output_dir)
-            .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+/// Validates all ND-JSON files in a directory using sonic-rs and returns a report with a summary and detailed errors
+pub fn validate_directory_with_summary_sonic(
+    dir_path: &Path,
+    config: &ValidatorConfig,
+) -> Result<Report> {
+    validate_directory_with_summary(dir_path, config, Backend::Sonic)
+}
+
+fn process_file(file_path: &Path, config: &ValidatorConfig, backend: Backend) -> Result<Vec<ValidationError>> {
+    scan_file(file_path, config, backend).map(|scan| scan.errors)
+}
+
+/// Like `process_file`, but also reports how many lines were read
+fn scan_file(file_path: &Path, config: &ValidatorConfig, backend: Backend) -> Result<Scan> {
+    // Lets library users get per-file timings out of their own subscriber
+    #[cfg(feature = "tracing")]
+    let _span = tracing::span!(tracing::Level::DEBUG, "validate_file", path = ?file_path).entered();
+
+    if let Some(hook) = &config.pre_file_hook {
+        (hook.0)(file_path);
+    }
 
-        let relative_path = file_path.file_name().unwrap_or_default();
-        let output_path = output_dir.join(relative_path);
+    let scan = scan_and_clean_file(file_path, config, backend)?;
 
-        clean_file(file_path, &output_path, &errors)?;
+    if let Some(hook) = &config.post_file_hook {
+        (hook.0)(file_path, &scan.errors);
     }
 
-    Ok(errors)
+    Ok(scan)
 }
 
-/// Validates a list of ND-JSON files using sonic-rs
-pub fn validate_files_sonic(
+fn scan_and_clean_file(file_path: &Path, config: &ValidatorConfig, backend: Backend) -> Result<Scan> {
+    if let Some(limit) = config.max_file_size_bytes {
+        let size = fs::metadata(file_path)?.len();
+        if size > limit {
+            return Err(NdJsonError::FileTooLarge {
+                path: file_path.to_path_buf(),
+                size,
+                limit,
+            });
+        }
+    }
+
+    let cleaning = config.output_dir.is_some() && config.clean_files;
+    if cleaning && is_named_pipe(file_path) {
+        return Err(read_twice_error(file_path, "clean"));
+    }
+
+    let file = File::open(file_path)?;
+
+    let Some(output_dir) = config.output_dir.as_ref().filter(|_| config.clean_files) else {
+        return scan_reader(BufReader::new(file), file_path, config, backend, config.error_limit_per_file());
+    };
+
+    // Cleaning needs every invalid line, so the per-file limit only applies to what is reported
+    let Scan { mut errors, lines_scanned, .. } = scan_reader(BufReader::new(file), file_path, config, backend, None)?;
+
+    fs::create_dir_all(output_dir)
+        .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+
+    let relative_path = file_path.file_name().unwrap_or_default();
+    let output_path = output_dir.join(relative_path);
+
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::DEBUG, "clean_file", output = ?output_path).entered();
+        clean_file(file_path, &output_path, &errors, config)?;
+    }
+
+    let limit = config.error_limit_per_file().unwrap_or(usize::MAX);
+    let truncated = errors.len() > limit;
+    errors.truncate(limit);
+
+    Ok(Scan { errors, lines_scanned, truncated })
+}
+
+/// Errors found in a batch of files
+struct Batch {
+    errors: Vec<ValidationError>,
+    /// Whether errors past `ValidatorConfig::max_errors_per_file` or
+    /// `ValidatorConfig::max_total_errors` were left out
+    truncated: bool,
+}
+
+/// Validates `files`, skipping the ones not yet started once `cancelled` is set, in which case
+/// `NdJsonError::Cancelled` is returned
+fn validate_files(
     files: &[PathBuf],
     config: &ValidatorConfig,
-) -> Result<Vec<ValidationError>> {
-    let results = files
-        .par_iter()
-        .map(|file_path| process_file_sonic(file_path, config))
-        .collect::<Vec<Result<Vec<ValidationError>>>>();
+    backend: Backend,
+    cancelled: Option<&AtomicBool>,
+) -> Result<Batch> {
+    match config.threads {
+        Some(threads) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(io::Error::other)?;
+            pool.install(|| validate_files_in_current_pool(files, config, backend, cancelled))
+        }
+        None => validate_files_in_current_pool(files, config, backend, cancelled),
+    }
+}
+
+fn validate_files_in_current_pool(
+    files: &[PathBuf],
+    config: &ValidatorConfig,
+    backend: Backend,
+    cancelled: Option<&AtomicBool>,
+) -> Result<Batch> {
+    #[cfg(feature = "tracing")]
+    if config.warn_on_task_overflow {
+        let threads = rayon::current_num_threads();
+        if files.len() > threads * 4 {
+            tracing::warn!(
+                files = files.len(),
+                threads,
+                "validating many more files than there are threads; per-file task overhead may dominate"
+            );
+        }
+    }
+
+    let error_rate = ErrorRate::default();
+    let progress = config.progress_file.as_deref().map(|path| Progress::new(path, files.len()));
+    let validate_file = |file_path: &PathBuf| {
+        if cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed)) {
+            return Ok(None);
+        }
+        let scan = scan_file(file_path, config, backend)?;
+        if let Some(progress) = &progress {
+            progress.record(file_path, scan.errors.len())?;
+        }
+        if let Some(threshold) = config.max_error_rate {
+            error_rate.record(&scan, threshold)?;
+        }
+        Ok(Some(scan))
+    };
+
+    // With `max_total_errors`, files are validated a pool's worth at a time and counted in input
+    // order, so the files skipped once the batch goes past the limit don't depend on scheduling
+    let chunk_size = match config.max_total_errors {
+        Some(_) => rayon::current_num_threads(),
+        None => files.len().max(1),
+    };
 
-    // Flatten results and collect errors
     let mut all_errors = Vec::new();
-    for result in results {
-        match result {
-            Ok(errors) => all_errors.extend(errors),
-            Err(e) => return Err(e),
+    let mut truncated = false;
+    let mut files_processed = 0;
+    let mut files_skipped = false;
+    'chunks: for chunk in files.chunks(chunk_size) {
+        let results = if config.bucket_by_size {
+            validate_in_size_buckets(chunk, validate_file)?
+        } else {
+            chunk.par_iter().map(validate_file).collect::<Vec<Result<Option<Scan>>>>()
+        };
+
+        for result in results {
+            // `None` means the file was skipped after cancellation
+            let Some(scan) = result? else {
+                files_skipped = true;
+                continue;
+            };
+            files_processed += 1;
+            all_errors.extend(scan.errors);
+            truncated |= scan.truncated;
+            if config.max_total_errors.is_some_and(|limit| all_errors.len() > limit) {
+                break 'chunks;
+            }
+        }
+    }
+
+    truncated |= cap_total_errors(&mut all_errors, config);
+    sort_errors(&mut all_errors, files, config);
+
+    if files_skipped {
+        return Err(NdJsonError::Cancelled(summarize(files_processed, &all_errors, truncated, config)));
+    }
+
+    Ok(Batch { errors: all_errors, truncated })
+}
+
+/// Cuts `errors` down to `ValidatorConfig::max_total_errors`, returning whether any were left out
+fn cap_total_errors(errors: &mut Vec<ValidationError>, config: &ValidatorConfig) -> bool {
+    let limit = config.max_total_errors.unwrap_or(usize::MAX);
+    let truncated = errors.len() > limit;
+    errors.truncate(limit);
+    truncated
+}
+
+/// Sorts the errors of a batch as `ValidatorConfig::order_errors_by_input` or
+/// `ValidatorConfig::stable_output` ask for
+fn sort_errors(errors: &mut [ValidationError], files: &[PathBuf], config: &ValidatorConfig) {
+    // `sort_by` is stable, so errors on the same line keep their order
+    if config.order_errors_by_input {
+        let mut input_index = HashMap::new();
+        for (index, file_path) in files.iter().enumerate() {
+            input_index.entry(file_path.as_path()).or_insert(index);
+        }
+        errors.sort_by_key(|error| (input_index.get(error.file_path.as_path()).copied(), error.line_number));
+    } else if config.stable_output {
+        errors.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+    }
+}
+
+/// Files from this size up are validated in the medium bucket by `ValidatorConfig::bucket_by_size`
+const MEDIUM_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Files from this size up are validated in the large bucket by `ValidatorConfig::bucket_by_size`
+const LARGE_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Runs `validate_file` on every file, large files first on a quarter of the threads and then the
+/// medium and small files on all of them. Results are returned in the order of `files`.
+fn validate_in_size_buckets<T: Send>(
+    files: &[PathBuf],
+    validate_file: impl Fn(&PathBuf) -> Result<T> + Sync,
+) -> Result<Vec<Result<T>>> {
+    let mut buckets: [Vec<usize>; 3] = Default::default();
+    for (index, file_path) in files.iter().enumerate() {
+        // Unreadable files fail when they're validated, so their bucket doesn't matter
+        let size = fs::metadata(file_path).map_or(0, |metadata| metadata.len());
+        let bucket = match size {
+            size if size >= LARGE_FILE_BYTES => 0,
+            size if size >= MEDIUM_FILE_BYTES => 1,
+            _ => 2,
+        };
+        buckets[bucket].push(index);
+    }
+
+    let mut results: Vec<Option<Result<T>>> = files.iter().map(|_| None).collect();
+    let run = |bucket: &[usize]| bucket.par_iter().map(|&index| (index, validate_file(&files[index]))).collect::<Vec<_>>();
+
+    let [large, medium, small] = &buckets;
+    let large_results = if large.is_empty() {
+        Vec::new()
+    } else {
+        let threads = (rayon::current_num_threads() / 4).max(1);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(io::Error::other)?;
+        pool.install(|| run(large))
+    };
+
+    for (index, result) in large_results.into_iter().chain(run(medium)).chain(run(small)) {
+        results[index] = Some(result);
+    }
+
+    Ok(results.into_iter().map(|result| result.expect("every file is in a bucket")).collect())
+}
+
+/// Running error rate across the files of a batch, shared between Rayon workers
+#[derive(Default)]
+struct ErrorRate {
+    errors: AtomicUsize,
+    lines: AtomicUsize,
+    files: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl ErrorRate {
+    /// Adds a processed file to the running totals, failing if the error rate is now above
+    /// `threshold`. Once tripped, every later file fails too so the batch stops quickly.
+    fn record(&self, scan: &Scan, threshold: f64) -> Result<()> {
+        let errors = self.errors.fetch_add(scan.errors.len(), Ordering::Relaxed) + scan.errors.len();
+        let lines = self.lines.fetch_add(scan.lines_scanned, Ordering::Relaxed) + scan.lines_scanned;
+        let files = self.files.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let current_rate = if lines == 0 { 0.0 } else { errors as f64 / lines as f64 };
+        if current_rate > threshold || self.exceeded.load(Ordering::Relaxed) {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return Err(NdJsonError::ErrorRateExceeded {
+                current_rate,
+                threshold,
+                files_processed: files,
+            });
         }
+
+        Ok(())
     }
+}
 
-    Ok(all_errors)
+/// Progress of a batch, written to `ValidatorConfig::progress_file` and shared between Rayon workers
+struct Progress<'a> {
+    path: &'a Path,
+    files_total: usize,
+    /// Files done and errors found so far. Held while writing, so that writes from different
+    /// workers can't overtake each other.
+    counts: Mutex<(usize, usize)>,
 }
 
-/// Validates multiple ND-JSON files using sonic-rs and returns a summary along with detailed errors
-pub fn validate_files_with_summary_sonic(
+impl<'a> Progress<'a> {
+    fn new(path: &'a Path, files_total: usize) -> Self {
+        Self {
+            path,
+            files_total,
+            counts: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Adds a finished file and atomically replaces the progress file
+    fn record(&self, file_path: &Path, errors: usize) -> Result<()> {
+        let mut counts = self.counts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        counts.0 += 1;
+        counts.1 += errors;
+
+        let progress_file = json!({
+            "files_done": counts.0,
+            "files_total": self.files_total,
+            "errors_so_far": counts.1,
+            "current_file": file_path.display().to_string(),
+        });
+
+        let mut temp_path = self.path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        fs::write(&temp_path, serde_json::to_vec(&progress_file).map_err(io::Error::from)?)?;
+        fs::rename(&temp_path, self.path)?;
+
+        Ok(())
+    }
+}
+
+fn validate_files_with_summary(
     files: &[PathBuf],
     config: &ValidatorConfig,
-) -> Result<(ValidationSummary, Vec<ValidationError>)> {
-    let errors = validate_files_sonic(files, config)?;
-
-    // Count unique files with errors
-    let files_with_errors = errors
-        .iter()
-        .map(|e| &e.file_path)
-        .collect::<HashSet<_>>()
-        .len();
-
-    let summary = ValidationSummary {
-        total_files: files.len(),
-        files_with_errors,
-        total_errors: errors.len(),
+    backend: Backend,
+) -> Result<Report> {
+    #[cfg(not(feature = "sqlite"))]
+    if config.sqlite_error_log.is_some() {
+        return Err(NdJsonError::InvalidConfig(
+            "sqlite_error_log requires the `sqlite` feature".to_string(),
+        ));
+    }
+
+    if has_warning_checks(config) {
+        if let Some(pipe) = files.iter().find(|file_path| is_named_pipe(file_path)) {
+            return Err(read_twice_error(pipe, "run warning checks on"));
+        }
+    }
+
+    let Batch { mut errors, mut truncated } = validate_files(files, config, backend, None)?;
+    // Opening a pipe blocks until something writes to it, so files are only opened again if needed
+    let mut warnings = if has_warning_checks(config) {
+        files
+            .par_iter()
+            .map(|file_path| find_warnings(BufReader::new(File::open(file_path)?), file_path, config))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect()
+    } else {
+        Vec::new()
     };
+    // Files have been cleaned by now, so promoted warnings don't remove any lines
+    promote_warnings(&mut errors, &mut warnings, config);
+    truncated |= cap_total_errors(&mut errors, config);
+    sort_errors(&mut errors, files, config);
+    let summary = summarize(files.len(), &errors, truncated, config);
+
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &config.sqlite_error_log {
+        write_error_log(path, &errors)?;
+    }
+
+    if let Some(output_dir) = config.output_dir.as_ref().filter(|_| config.clean_files && config.write_summary_file) {
+        write_summary_file(output_dir, &summary, files)?;
+    }
 
-    Ok((summary, errors))
+    Ok(Report {
+        summary,
+        errors,
+        warnings,
+        format: config.output_format,
+    })
 }
 
-/// Validates all ND-JSON files in a directory using sonic-rs and returns a summary along with detailed errors
-pub fn validate_directory_with_summary_sonic(
+fn write_summary_file(output_dir: &Path, summary: &ValidationSummary, files: &[PathBuf]) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    // `timestamp` is in seconds since the Unix epoch
+    let summary_file = json!({
+        "schema_version": SUMMARY_FILE_SCHEMA_VERSION,
+        "timestamp": timestamp,
+        "summary": summary_json(summary),
+        "files": files.iter().map(|file| file.display().to_string()).collect::<Vec<_>>(),
+    });
+
+    fs::create_dir_all(output_dir)
+        .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+    let mut writer = BufWriter::new(File::create(output_dir.join(SUMMARY_FILE_NAME))?);
+    serde_json::to_writer_pretty(&mut writer, &summary_file).map_err(io::Error::from)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Value of `field` in the first valid JSON line of a file, or `Value::Null` if it has none
+fn partition_key(file_path: &Path, field: &str, config: &ValidatorConfig) -> Result<Value> {
+    let file = File::open(file_path)?;
+    let mut checker = LineChecker::new(config);
+
+    for line in Lines::new(BufReader::new(file), file_path) {
+        let line = line?;
+        if line.number <= config.skip_header_lines || checker.is_schema_header(&line.content, line.number, config) {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(&line.content) {
+            return Ok(value.get(field).cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Summary of the errors of a batch, where `truncated` says whether the scan left any errors out
+pub(crate) fn summarize(total_files: usize, errors: &[ValidationError], truncated: bool, config: &ValidatorConfig) -> ValidationSummary {
+    let mut errors_per_file: HashMap<&Path, usize> = HashMap::new();
+    for error in errors {
+        *errors_per_file.entry(&error.file_path).or_default() += 1;
+    }
+
+    ValidationSummary {
+        total_files,
+        files_with_errors: errors_per_file.len(),
+        total_errors: errors.len(),
+        partial_scan: config.first_line_only || config.line_range.is_some(),
+        truncated,
+    }
+}
+
+fn validate_directory_with_summary(
     dir_path: &Path,
     config: &ValidatorConfig,
-) -> Result<(ValidationSummary, Vec<ValidationError>)> {
+    backend: Backend,
+) -> Result<Report> {
+    let mut file_paths = find_ndjson_files(dir_path, config)?;
+    if config.sort_files {
+        file_paths.sort();
+    }
+
+    if let Some(expected) = config.min_files_expected {
+        if file_paths.len() < expected {
+            return Err(NdJsonError::TooFewFiles {
+                found: file_paths.len(),
+                expected,
+            });
+        }
+    }
+
+    if file_paths.is_empty() {
+        return Err(NdJsonError::NoFilesFound(dir_path.display().to_string()));
+    }
+
+    validate_files_with_summary(&file_paths, config, backend)
+}
+
+/// Finds all ND-JSON files directly inside a directory
+fn find_ndjson_files(dir_path: &Path, config: &ValidatorConfig) -> Result<Vec<PathBuf>> {
     let mut file_paths = Vec::new();
 
-    // Find all NDJSON files in the directory
-    for entry_result in WalkDir::new(dir_path).max_depth(1).into_iter() {
-        let entry = entry_result?;
+    for entry_result in WalkDir::new(dir_path).max_depth(1).into_iter().filter_entry(|entry| !is_skipped_hidden(entry, config)) {
+        let entry = entry_result.map_err(|source| NdJsonError::DirectoryTraversalError {
+            path: source.path().unwrap_or(dir_path).to_path_buf(),
+            source,
+        })?;
         let path = entry.path();
-        if path.is_file()
-            && (path
-                .extension()
-                .map_or(false, |ext| ext == "ndjson" || ext == "jsonl")
-                || path.to_string_lossy().contains(".nd.json"))
-        {
+        if is_candidate_file(path, config) && is_ndjson_path(path) {
             file_paths.push(path.to_path_buf());
         }
     }
 
-    if file_paths.is_empty() {
-        return Err(NdJsonError::NoFilesFound(dir_path.display().to_string()));
+    Ok(file_paths)
+}
+
+/// Whether a directory entry can be validated: a regular file or, with
+/// `ValidatorConfig::allow_named_pipes`, a named pipe
+fn is_candidate_file(path: &Path, config: &ValidatorConfig) -> bool {
+    path.is_file() || (config.allow_named_pipes && is_named_pipe(path))
+}
+
+/// Whether a path is a named pipe (FIFO). Always `false` outside Unix.
+fn is_named_pipe(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo())
     }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
 
-    validate_files_with_summary_sonic(&file_paths, config)
+/// Error for an operation that would have to read a named pipe a second time
+fn read_twice_error(path: &Path, operation: &str) -> NdJsonError {
+    NdJsonError::InvalidConfig(format!(
+        "cannot {} named pipe {}, since it can only be read once",
+        operation,
+        path.display()
+    ))
+}
+
+/// Whether `ValidatorConfig::skip_hidden` excludes a directory entry. The root of the walk is
+/// never excluded.
+fn is_skipped_hidden(entry: &DirEntry, config: &ValidatorConfig) -> bool {
+    config.skip_hidden && entry.depth() > 0 && entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// Whether a path looks like an ND-JSON file based on its name
+pub(crate) fn is_ndjson_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "ndjson" || ext == "jsonl")
+        || path.to_string_lossy().contains(".nd.json")
 }
 
 #[cfg(test)]
@@ -207,6 +911,7 @@ mod tests {
         let config = ValidatorConfig {
             clean_files: true,
             output_dir: Some(output_dir.to_path_buf()),
+            ..Default::default()
         };
 
         let errors = process_file_serde(file_path, &config).unwrap();
@@ -225,6 +930,29 @@ mod tests {
         assert!(content.contains("Charlie"));
     }
 
+    #[test]
+    fn test_latin1_encoding_fallback() {
+        let file_path = Path::new("tests/fixtures/latin1.ndjson");
+        assert!(matches!(
+            process_file_serde(file_path, &ValidatorConfig::default()),
+            Err(NdJsonError::UnsupportedEncoding { byte_offset: 13, .. })
+        ));
+
+        let temp_dir = tempdir().unwrap();
+        let config = ValidatorConfig {
+            encoding_fallback: true,
+            clean_files: true,
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        assert!(process_file_serde(file_path, &config).unwrap().is_empty());
+
+        // The cleaned copy is transcoded to UTF-8
+        let cleaned = fs::read_to_string(temp_dir.path().join("latin1.ndjson")).unwrap();
+        assert!(cleaned.starts_with("{\"name\": \"José\", \"city\": \"São Paulo\"}\n"));
+        assert_eq!(cleaned.lines().count(), 3);
+    }
+
     #[test]
     fn test_no_cleaning_when_disabled() {
         let temp_dir = tempdir().unwrap();
@@ -234,6 +962,7 @@ mod tests {
         let config = ValidatorConfig {
             clean_files: false, // Cleaning disabled
             output_dir: Some(output_dir.to_path_buf()),
+            ..Default::default()
         };
 
         let errors = process_file_serde(file_path, &config).unwrap();
@@ -253,6 +982,7 @@ mod tests {
         let config = ValidatorConfig {
             clean_files: true, // Cleaning enabled
             output_dir: Some(output_dir.to_path_buf()),
+            ..Default::default()
         };
 
         let errors = process_file_serde(file_path, &config).unwrap();
@@ -281,6 +1011,7 @@ mod tests {
         let config = ValidatorConfig {
             clean_files: true,
             output_dir: Some(output_dir_path.to_path_buf()),
+            ..Default::default()
         };
 
         let errors = process_file_serde(&input_file_path, &config).unwrap();
@@ -319,6 +1050,7 @@ mod tests {
         let parallel_config = ValidatorConfig {
             clean_files: false,
             output_dir: None,
+            ..Default::default()
         };
         let parallel_errors = validate_files_serde(&files, &parallel_config).unwrap();
 
@@ -327,6 +1059,64 @@ mod tests {
 
 
 
+    #[test]
+    fn test_validate_files_to_ndjson_writer() {
+        let files = vec![PathBuf::from("tests/valid.ndjson"), PathBuf::from("tests/invalid1.ndjson")];
+        let mut output = Vec::new();
+        validate_files_to_ndjson_writer_serde(&files, &ValidatorConfig::default(), &mut output).unwrap();
+        
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["file_path"], "tests/invalid1.ndjson");
+        assert_eq!(lines[0]["line_number"], 1);
+        assert!(lines[0]["error"].is_string());
+        assert!(lines[0]["line_content"].is_string());
+    }
+
+    #[test]
+    fn test_bucket_by_size_keeps_file_order() {
+        let temp_dir = tempdir().unwrap();
+        let big_path = temp_dir.path().join("big.ndjson");
+        // Just over the medium threshold, with an invalid last line
+        let line = format!("{{\"payload\": \"{}\"}}\n", "x".repeat(1000));
+        fs::write(&big_path, format!("{}{{\"a\": }}\n", line.repeat(MEDIUM_FILE_BYTES as usize / line.len() + 1))).unwrap();
+        let files = vec![
+            PathBuf::from("tests/invalid1.ndjson"),
+            big_path.clone(),
+            PathBuf::from("tests/invalid2.ndjson"),
+        ];
+        
+        let config = ValidatorConfig {
+            bucket_by_size: true,
+            ..Default::default()
+        };
+        let bucketed = validate_files_serde(&files, &config).unwrap();
+        let unbucketed = validate_files_serde(&files, &ValidatorConfig::default()).unwrap();
+        
+        let positions = |errors: &[ValidationError]| -> Vec<(PathBuf, usize)> {
+            errors.iter().map(|e| (e.file_path.clone(), e.line_number)).collect()
+        };
+        assert_eq!(positions(&bucketed), positions(&unbucketed));
+        assert_eq!(bucketed.len(), 10);
+        assert_eq!(bucketed[1].file_path, big_path);
+    }
+
+    #[test]
+    fn test_validate_string_batch() {
+        let inputs = (0..20)
+            .map(|i| (format!("input-{}", i), "{\"a\": 1}\n{\"a\": }\n".repeat(i)))
+            .collect();
+
+        let results = validate_string_batch_serde(inputs);
+        assert_eq!(results.len(), 20);
+        for (i, (label, errors)) in results.iter().enumerate() {
+            assert_eq!(label, &format!("input-{}", i));
+            assert_eq!(errors.len(), i);
+            assert!(errors.iter().all(|e| e.file_path == Path::new(label)));
+        }
+    }
+
     #[test]
     fn test_validation_summary() {
         let files = vec![
@@ -336,7 +1126,7 @@ mod tests {
         ];
 
         let config = ValidatorConfig::default();
-        let (summary, errors) = validate_files_with_summary_serde(&files, &config).unwrap();
+        let Report { summary, errors, .. } = validate_files_with_summary_serde(&files, &config).unwrap();
 
         assert_eq!(summary.total_files, 3);
         assert_eq!(summary.files_with_errors, 2); // valid.ndjson has no errors
@@ -346,11 +1136,837 @@ mod tests {
     #[test]
     fn test_directory_summary() {
         let config = ValidatorConfig::default();
-        let (summary, errors) =
+        let Report { summary, errors, .. } =
             validate_directory_with_summary_serde(Path::new("tests"), &config).unwrap();
 
         assert_eq!(summary.total_files, 3); // valid.ndjson, invalid1.ndjson, invalid2.ndjson
         assert_eq!(summary.files_with_errors, 2); // Two files with errors
         assert_eq!(summary.total_errors, errors.len());
     }
+
+    #[test]
+    fn test_directory_files_are_sorted() {
+        let temp_dir = tempdir().unwrap();
+        for name in ["c.ndjson", "a.ndjson", "b.jsonl"] {
+            fs::write(temp_dir.path().join(name), "{\"a\": }\n").unwrap();
+        }
+
+        let Report { errors, .. } =
+            validate_directory_with_summary_serde(temp_dir.path(), &ValidatorConfig::default()).unwrap();
+        let names: Vec<_> = errors.iter().map(|e| e.file_path.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, ["a.ndjson", "b.jsonl", "c.ndjson"]);
+    }
+
+    #[test]
+    fn test_cleaning_keeps_comment_lines() {
+        let temp_dir = tempdir().unwrap();
+        let input_path = temp_dir.path().join("comments.ndjson");
+        fs::write(&input_path, "// source: billing\n{\"a\": 1}\n{\"a\": \n").unwrap();
+        let output_dir = temp_dir.path().join("cleaned");
+
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(output_dir.clone()),
+            allow_comment_lines: true,
+            ..Default::default()
+        };
+
+        let errors = process_file_serde(&input_path, &config).unwrap();
+        assert_eq!(errors.len(), 1);
+
+        let content = fs::read_to_string(output_dir.join("comments.ndjson")).unwrap();
+        assert_eq!(content, "// source: billing\n{\"a\": 1}\n");
+    }
+
+    #[test]
+    fn test_write_summary_file() {
+        let temp_dir = tempdir().unwrap();
+        let files = vec![
+            PathBuf::from("tests/valid.ndjson"),
+            PathBuf::from("tests/invalid1.ndjson"),
+        ];
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            write_summary_file: true,
+            ..Default::default()
+        };
+
+        validate_files_with_summary_serde(&files, &config).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join(SUMMARY_FILE_NAME)).unwrap();
+        let summary: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(summary["schema_version"], 1);
+        assert!(summary["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(summary["summary"]["total_files"], 2);
+        assert_eq!(summary["summary"]["total_errors"], 1);
+        assert_eq!(summary["files"], serde_json::json!(["tests/valid.ndjson", "tests/invalid1.ndjson"]));
+    }
+
+    #[test]
+    fn test_cleaning_keeps_header_lines() {
+        let temp_dir = tempdir().unwrap();
+        let input_path = temp_dir.path().join("with_header.ndjson");
+        fs::write(&input_path, "vendor export v2\n{\"a\": 1}\n{\"a\": \n").unwrap();
+        let output_dir = temp_dir.path().join("cleaned");
+
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(output_dir.clone()),
+            skip_header_lines: 1,
+            ..Default::default()
+        };
+
+        let errors = process_file_serde(&input_path, &config).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 3);
+
+        let content = fs::read_to_string(output_dir.join("with_header.ndjson")).unwrap();
+        assert_eq!(content, "vendor export v2\n{\"a\": 1}\n");
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        let size = fs::metadata("tests/valid.ndjson").unwrap().len();
+
+        let config = ValidatorConfig {
+            max_file_size_bytes: Some(size),
+            ..Default::default()
+        };
+        assert!(process_file_serde(Path::new("tests/valid.ndjson"), &config).is_ok());
+
+        let config = ValidatorConfig {
+            max_file_size_bytes: Some(size - 1),
+            ..Default::default()
+        };
+        let files = vec![PathBuf::from("tests/valid.ndjson")];
+        match validate_files_sonic(&files, &config) {
+            Err(NdJsonError::FileTooLarge { size: actual, limit, .. }) => {
+                assert_eq!(actual, size);
+                assert_eq!(limit, size - 1);
+            }
+            other => panic!("expected FileTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_directory() {
+        let temp_dir = tempdir().unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        match validate_directory_with_summary_serde(&missing, &ValidatorConfig::default()) {
+            Err(NdJsonError::DirectoryTraversalError { path, source }) => {
+                assert_eq!(path, missing);
+                assert_eq!(source.io_error().unwrap().kind(), io::ErrorKind::NotFound);
+            }
+            other => panic!("expected DirectoryTraversalError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_min_files_expected() {
+        let config = ValidatorConfig {
+            min_files_expected: Some(3),
+            ..Default::default()
+        };
+        assert!(validate_directory_with_summary_serde(Path::new("tests"), &config).is_ok());
+
+        let config = ValidatorConfig {
+            min_files_expected: Some(4),
+            ..Default::default()
+        };
+        match validate_directory_with_summary_sonic(Path::new("tests"), &config) {
+            Err(NdJsonError::TooFewFiles { found, expected }) => {
+                assert_eq!(found, 3);
+                assert_eq!(expected, 4);
+            }
+            other => panic!("expected TooFewFiles, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let files = vec![
+            PathBuf::from("tests/valid.ndjson"),
+            PathBuf::from("tests/invalid1.ndjson"),
+        ];
+        let config = ValidatorConfig::default();
+
+        let errors = validate_files_cancellable_serde(&files, &config, Arc::new(AtomicBool::new(false))).unwrap();
+        assert_eq!(errors.len(), 1);
+
+        // Goes through the same batch path as `validate_files_serde`, so batch options apply
+        let files = vec![PathBuf::from("tests/invalid2.ndjson"), PathBuf::from("tests/invalid1.ndjson")];
+        let batch_config = ValidatorConfig {
+            threads: Some(1),
+            max_total_errors: Some(3),
+            stable_output: true,
+            ..Default::default()
+        };
+        let errors = validate_files_cancellable_serde(&files, &batch_config, Arc::new(AtomicBool::new(false))).unwrap();
+        assert_eq!(errors, validate_files_serde(&files, &batch_config).unwrap());
+        assert_eq!(errors.len(), 3);
+
+        match validate_files_cancellable_serde(&files, &config, Arc::new(AtomicBool::new(true))) {
+            Err(NdJsonError::Cancelled(summary)) => {
+                assert_eq!(summary.total_files, 0);
+                assert_eq!(summary.total_errors, 0);
+            }
+            other => panic!("expected Cancelled, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_tarball() {
+        let temp_dir = tempdir().unwrap();
+        let archive_path = temp_dir.path().join("batch.tar.gz");
+
+        let encoder = flate2::write::GzEncoder::new(File::create(&archive_path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, source) in [
+            ("data/valid.ndjson", "tests/valid.ndjson"),
+            ("data/invalid1.ndjson", "tests/invalid1.ndjson"),
+            ("README.txt", "Cargo.toml"),
+        ] {
+            builder.append_path_with_name(source, name).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let output_dir = temp_dir.path().join("cleaned");
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(output_dir.clone()),
+            ..Default::default()
+        };
+        let Report { summary, errors, .. } = validate_tarball_serde(&archive_path, &config).unwrap();
+
+        assert_eq!(summary.total_files, 2);
+        assert_eq!(summary.total_errors, 1);
+        assert_eq!(errors[0].file_path, archive_path.join("data/invalid1.ndjson"));
+
+        let cleaned = fs::read_to_string(output_dir.join("invalid1.ndjson")).unwrap();
+        assert_eq!(cleaned.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_max_error_rate() {
+        let files = vec![
+            PathBuf::from("tests/valid.ndjson"),
+            PathBuf::from("tests/invalid2.ndjson"),
+        ];
+
+        let config = ValidatorConfig {
+            max_error_rate: Some(0.9),
+            ..Default::default()
+        };
+        assert_eq!(validate_files_serde(&files, &config).unwrap().len(), 8);
+
+        let config = ValidatorConfig {
+            max_error_rate: Some(0.1),
+            ..Default::default()
+        };
+        match validate_files_sonic(&files, &config) {
+            Err(NdJsonError::ErrorRateExceeded { current_rate, threshold, files_processed }) => {
+                assert!(current_rate > 0.1);
+                assert_eq!(threshold, 0.1);
+                assert!(files_processed >= 1);
+            }
+            other => panic!("expected ErrorRateExceeded, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_partition_by_field() {
+        let temp_dir = tempdir().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let files = vec![
+            write("a1.ndjson", "{\"tenant\": \"a\"}\n{\"x\": }\n"),
+            write("a2.ndjson", "{\"y\": }\n{\"tenant\": \"a\"}\n"),
+            write("b.ndjson", "{\"tenant\": \"b\"}\n"),
+            write("none.ndjson", "{\"other\": 1}\n"),
+        ];
+
+        assert!(matches!(
+            validate_files_partitioned_serde(&files, &ValidatorConfig::default()),
+            Err(NdJsonError::InvalidConfig(_))
+        ));
+
+        let config = ValidatorConfig {
+            partition_by_field: Some("tenant".to_string()),
+            ..Default::default()
+        };
+        let reports = validate_files_partitioned_serde(&files, &config).unwrap();
+        assert_eq!(reports.len(), 3);
+
+        let a = &reports[&Value::from("a")];
+        assert_eq!(a.summary.total_files, 2);
+        assert_eq!(a.summary.total_errors, 2);
+        assert!(reports[&Value::from("b")].summary.is_clean());
+        assert_eq!(reports[&Value::Null].summary.total_files, 1);
+    }
+
+    #[test]
+    fn test_warnings_as_errors() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("mixed.ndjson");
+        let output_dir = temp_dir.path().join("cleaned");
+        let content = "{\"a\": 1}\n{\"a\": 2}\r\n{\"a\": 3}\n";
+        fs::write(&file_path, content).unwrap();
+
+        let config = ValidatorConfig {
+            check_newline_consistency: true,
+            warnings_as_errors: true,
+            clean_files: true,
+            output_dir: Some(output_dir.clone()),
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&[file_path], &config).unwrap();
+
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.summary.total_errors, 1);
+        assert_eq!(report.summary.files_with_errors, 1);
+        assert_eq!(report.errors[0].line_number, 2);
+        assert!(report.errors[0].error.contains("mixes newline styles"));
+
+        // The line warned about is valid, so cleaning keeps it
+        assert_eq!(fs::read_to_string(output_dir.join("mixed.ndjson")).unwrap(), "{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n");
+    }
+
+    #[test]
+    fn test_validate_directory_leveled() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("top.ndjson"), "{\"a\": 1}\n").unwrap();
+        fs::write(root.join("a").join("one.ndjson"), "{\"a\": }\n{\"a\": }\n").unwrap();
+        fs::write(root.join("a").join("two.jsonl"), "{\"a\": 1}\n").unwrap();
+        fs::write(nested.join("deep.ndjson"), "{\"a\": }\n").unwrap();
+
+        let levels = validate_directory_leveled_serde(root, 1, &ValidatorConfig::default()).unwrap();
+        let stats = levels
+            .iter()
+            .map(|(depth, summary)| (*depth, summary.total_files, summary.files_with_errors, summary.total_errors))
+            .collect::<Vec<_>>();
+        assert_eq!(stats, vec![(0, 1, 0, 0), (1, 2, 1, 2)]);
+
+        let levels = validate_directory_leveled_serde(root, 3, &ValidatorConfig::default()).unwrap();
+        let stats = levels.iter().map(|(depth, summary)| (*depth, summary.total_errors)).collect::<Vec<_>>();
+        assert_eq!(stats, vec![(0, 0), (1, 2), (2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_skip_hidden() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let hidden_dir = root.join(".hidden");
+        fs::create_dir_all(&hidden_dir).unwrap();
+        fs::write(root.join("visible.ndjson"), "{\"a\": 1}\n").unwrap();
+        fs::write(root.join(".backup.ndjson"), "{\"a\": }\n").unwrap();
+        fs::write(hidden_dir.join("nested.ndjson"), "{\"a\": }\n").unwrap();
+
+        let report = validate_directory_with_summary_serde(root, &ValidatorConfig::default()).unwrap();
+        assert_eq!(report.summary.total_files, 1);
+        let levels = validate_directory_leveled_serde(root, 1, &ValidatorConfig::default()).unwrap();
+        assert!(levels.iter().all(|(_, summary)| summary.total_errors == 0));
+
+        let config = ValidatorConfig {
+            skip_hidden: false,
+            ..Default::default()
+        };
+        let report = validate_directory_with_summary_serde(root, &config).unwrap();
+        assert_eq!(report.summary.total_files, 2);
+        let levels = validate_directory_leveled_serde(root, 1, &config).unwrap();
+        assert_eq!(levels[1].1.total_errors, 1);
+
+        // A hidden directory is still searched when it's the one being validated
+        let report = validate_directory_with_summary_serde(&hidden_dir, &ValidatorConfig::default()).unwrap();
+        assert_eq!(report.summary.total_files, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allow_named_pipes() {
+        use std::process::Command;
+
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let pipe = root.join("events.ndjson");
+        assert!(Command::new("mkfifo").arg(&pipe).status().unwrap().success());
+        fs::write(root.join("static.ndjson"), "{\"a\": 1}\n").unwrap();
+
+        // Pipes are left alone by default, so nothing blocks on this one
+        let report = validate_directory_with_summary_serde(root, &ValidatorConfig::default()).unwrap();
+        assert_eq!(report.summary.total_files, 1);
+
+        let config = ValidatorConfig {
+            allow_named_pipes: true,
+            ..Default::default()
+        };
+        let writer = std::thread::spawn({
+            let pipe = pipe.clone();
+            move || fs::write(pipe, "{\"a\": 1}\n{\"a\": }\n").unwrap()
+        });
+        let report = validate_directory_with_summary_serde(root, &config).unwrap();
+        writer.join().unwrap();
+        assert_eq!(report.summary.total_files, 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].file_path, pipe);
+
+        // Checked before the pipe is opened, so there's no writer to wait for
+        let config = ValidatorConfig {
+            check_newline_consistency: true,
+            ..config
+        };
+        let error = validate_files_with_summary_serde(std::slice::from_ref(&pipe), &config).unwrap_err();
+        assert!(error.to_string().contains("can only be read once"));
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(root.join("cleaned")),
+            ..Default::default()
+        };
+        assert!(matches!(process_file_serde(&pipe, &config), Err(NdJsonError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_max_total_errors() {
+        use std::collections::HashSet;
+
+        let temp_dir = tempdir().unwrap();
+        let files: Vec<PathBuf> = (0..8)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("{}.ndjson", i));
+                fs::write(&path, "{\"a\": }\n{\"a\": }\n{\"a\": }\n").unwrap();
+                path
+            })
+            .collect();
+
+        let config = ValidatorConfig {
+            max_total_errors: Some(4),
+            threads: Some(1),
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+        assert_eq!(report.errors.len(), 4);
+        assert!(report.summary.truncated);
+        // With a single worker, files are started in order and the third is never started
+        assert_eq!(report.errors.iter().map(|e| e.file_path.clone()).collect::<HashSet<_>>().len(), 2);
+
+        let report = validate_files_with_summary_serde(&files, &ValidatorConfig::default()).unwrap();
+        assert_eq!(report.errors.len(), 24);
+        assert!(!report.summary.truncated);
+
+        // Reaching the limit exactly leaves nothing out
+        let config = ValidatorConfig {
+            max_total_errors: Some(24),
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+        assert_eq!(report.errors.len(), 24);
+        assert!(!report.summary.truncated);
+    }
+
+    #[test]
+    fn test_max_total_errors_keeps_the_same_errors_with_any_pool_size() {
+        let temp_dir = tempdir().unwrap();
+        let files: Vec<PathBuf> = (0..16)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("{:02}.ndjson", i));
+                fs::write(&path, "{\"a\": }\n{\"a\": 1}\n{\"a\": }\n").unwrap();
+                path
+            })
+            .collect();
+
+        for threads in [1, 2, 3, 8] {
+            let config = ValidatorConfig {
+                max_total_errors: Some(5),
+                threads: Some(threads),
+                ..Default::default()
+            };
+            let errors = validate_files_serde(&files, &config).unwrap();
+            assert_eq!(
+                errors.iter().map(|e| (e.file_path.clone(), e.line_number)).collect::<Vec<_>>(),
+                vec![
+                    (files[0].clone(), 1),
+                    (files[0].clone(), 3),
+                    (files[1].clone(), 1),
+                    (files[1].clone(), 3),
+                    (files[2].clone(), 1),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_total_errors_includes_promoted_warnings() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("escaped.ndjson");
+        fs::write(&file_path, "{\"a\": \"\\u0041\"}\n{\"a\": \"\\u0042\"}\n{\"a\": \"\\u0043\"}\n").unwrap();
+
+        let config = ValidatorConfig {
+            strict_unicode: true,
+            warnings_as_errors: true,
+            max_total_errors: Some(2),
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&[file_path], &config).unwrap();
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.summary.truncated);
+    }
+
+    #[test]
+    fn test_max_errors_per_file_truncates_summary() {
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(3),
+            ..Default::default()
+        };
+        let files = vec![PathBuf::from("tests/invalid1.ndjson"), PathBuf::from("tests/invalid2.ndjson")];
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+        assert_eq!(report.summary.total_errors, 4);
+        assert!(report.summary.truncated);
+
+        let report = validate_files_with_summary_serde(&files[..1], &config).unwrap();
+        assert!(!report.summary.truncated);
+
+        // A file with exactly as many errors as the limit has nothing left out
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(8),
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&files[1..], &config).unwrap();
+        assert_eq!(report.summary.total_errors, 8);
+        assert!(!report.summary.truncated);
+    }
+
+    #[test]
+    fn test_fail_fast_applies_per_file() {
+        let config = ValidatorConfig {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let files = vec![PathBuf::from("tests/invalid2.ndjson"), PathBuf::from("tests/invalid1.ndjson")];
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+
+        assert_eq!(report.errors.iter().map(|e| (e.file_path.clone(), e.line_number)).collect::<Vec<_>>(), vec![
+            (files[0].clone(), 2),
+            (files[1].clone(), 1),
+        ]);
+        assert!(report.summary.truncated);
+    }
+
+    #[test]
+    fn test_first_line_only() {
+        let temp_dir = tempdir().unwrap();
+        let good_start = temp_dir.path().join("good_start.ndjson");
+        let bad_start = temp_dir.path().join("bad_start.ndjson");
+        fs::write(&good_start, "\n{\"a\": 1}\n{\"a\": }\n").unwrap();
+        fs::write(&bad_start, "{\"a\": }\n{\"a\": }\n").unwrap();
+
+        let config = ValidatorConfig {
+            first_line_only: true,
+            ..Default::default()
+        };
+        let report = validate_files_with_summary_serde(&[good_start, bad_start.clone()], &config).unwrap();
+
+        assert!(report.summary.partial_scan);
+        assert_eq!(report.summary.total_errors, 1);
+        assert_eq!(report.errors[0].file_path, bad_start);
+        assert_eq!(report.errors[0].line_number, 1);
+
+        let report = validate_files_with_summary_serde(&[bad_start], &ValidatorConfig::default()).unwrap();
+        assert!(!report.summary.partial_scan);
+        assert_eq!(report.summary.total_errors, 2);
+    }
+
+    #[test]
+    fn test_order_errors_by_input() {
+        let temp_dir = tempdir().unwrap();
+        let mixed = temp_dir.path().join("mixed.ndjson");
+        fs::write(&mixed, "{\"a\": }\n{\"a\": 2}\r\n{\"a\": 3}\n").unwrap();
+        let files = vec![mixed.clone(), PathBuf::from("tests/invalid1.ndjson")];
+        let order = |report: &Report| {
+            report.errors.iter().map(|e| (e.file_path.clone(), e.line_number)).collect::<Vec<_>>()
+        };
+        let expected = vec![(mixed.clone(), 1), (mixed, 2), (files[1].clone(), 1)];
+
+        let mut config = ValidatorConfig {
+            check_newline_consistency: true,
+            warnings_as_errors: true,
+            ..Default::default()
+        };
+        // The promoted warning comes last
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+        assert_ne!(order(&report), expected);
+
+        config.order_errors_by_input = true;
+        let report = validate_files_with_summary_serde(&files, &config).unwrap();
+        assert_eq!(order(&report), expected);
+    }
+
+    #[test]
+    fn test_validate_and_stream_valid() {
+        let mut output = Vec::new();
+        let (valid_lines, errors) =
+            validate_and_stream_valid_serde(Path::new("tests/invalid1.ndjson"), &mut output, &ValidatorConfig::default()).unwrap();
+
+        assert_eq!(valid_lines, 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 1);
+
+        // Same result as validating and then cleaning
+        let content = fs::read_to_string("tests/invalid1.ndjson").unwrap();
+        let cleaned = crate::clean_content_serde(&content, &ValidatorConfig::default()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), cleaned);
+    }
+
+    #[test]
+    fn test_validate_and_stream_valid_error_limit() {
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(2),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let (_, errors) = validate_and_stream_valid_serde(Path::new("tests/invalid2.ndjson"), &mut output, &config).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![2, 3]);
+
+        // Lines past the limit are still left out of the output
+        let content = fs::read_to_string("tests/invalid2.ndjson").unwrap();
+        let cleaned = crate::clean_content_serde(&content, &ValidatorConfig::default()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), cleaned);
+    }
+
+    #[test]
+    fn test_validate_and_stream_valid_fail_fast() {
+        let config = ValidatorConfig {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let (valid_lines, errors) =
+            validate_and_stream_valid_serde(Path::new("tests/invalid2.ndjson"), &mut output, &config).unwrap();
+        assert_eq!(valid_lines, 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 2);
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"name\": \"Alice\", \"age\": 30}\n");
+
+        // Without cleaning, standard input goes through the same scan as a file
+        let content = fs::read("tests/invalid2.ndjson").unwrap();
+        let errors = validate_stdin(content.as_slice(), &config).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn test_validate_and_stream_valid_needs_full_scan() {
+        let partial_configs = [
+            ValidatorConfig {
+                first_line_only: true,
+                ..Default::default()
+            },
+            ValidatorConfig {
+                line_range: Some(2..3),
+                ..Default::default()
+            },
+        ];
+        for config in partial_configs {
+            let mut output = Vec::new();
+            let result = validate_and_stream_valid_serde(Path::new("tests/invalid1.ndjson"), &mut output, &config);
+            assert!(matches!(result, Err(NdJsonError::InvalidConfig(_))));
+            assert!(output.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_validate_stdin_partial_scan() {
+        let content = "{\"a\": 1}\n{\"a\": }\n{\"a\": }\n";
+        let config = ValidatorConfig {
+            first_line_only: true,
+            ..Default::default()
+        };
+        assert!(validate_stdin(content.as_bytes(), &config).unwrap().is_empty());
+
+        let config = ValidatorConfig {
+            line_range: Some(3..4),
+            ..Default::default()
+        };
+        let errors = validate_stdin(content.as_bytes(), &config).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![3]);
+
+        // Cleaning needs every line, so a partial scan is rejected before any output is written
+        let temp_dir = tempdir().unwrap();
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            first_line_only: true,
+            ..Default::default()
+        };
+        let result = validate_stdin(content.as_bytes(), &config);
+        assert!(matches!(result, Err(NdJsonError::InvalidConfig(_))));
+        assert!(!temp_dir.path().join("stdin.ndjson").exists());
+    }
+
+    #[test]
+    fn test_progress_file() {
+        let temp_dir = tempdir().unwrap();
+        let progress_path = temp_dir.path().join("progress.json");
+        let config = ValidatorConfig {
+            progress_file: Some(progress_path.clone()),
+            ..Default::default()
+        };
+
+        let files = vec![PathBuf::from("tests/valid.ndjson"), PathBuf::from("tests/invalid1.ndjson")];
+        let errors = validate_files_serde(&files, &config).unwrap();
+
+        let progress: Value = serde_json::from_str(&fs::read_to_string(&progress_path).unwrap()).unwrap();
+        assert_eq!(progress["files_done"], 2);
+        assert_eq!(progress["files_total"], 2);
+        assert_eq!(progress["errors_so_far"], errors.len());
+        assert!(files.iter().any(|file| progress["current_file"] == file.to_str().unwrap()));
+        assert!(!temp_dir.path().join("progress.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_stable_output() {
+        let files = vec![PathBuf::from("tests/invalid2.ndjson"), PathBuf::from("tests/invalid1.ndjson")];
+        let file_order = |errors: &[ValidationError]| {
+            errors.iter().map(|e| (e.file_path.clone(), e.line_number)).collect::<Vec<_>>()
+        };
+
+        let errors = validate_files_serde(&files, &ValidatorConfig::default()).unwrap();
+        assert_eq!(errors[0].file_path, files[0]);
+
+        let config = ValidatorConfig {
+            stable_output: true,
+            ..Default::default()
+        };
+        let errors = validate_files_serde(&files, &config).unwrap();
+        let mut expected = file_order(&errors);
+        expected.sort();
+        assert_eq!(file_order(&errors), expected);
+        assert_eq!(errors[0].file_path, files[1]);
+    }
+
+    #[test]
+    fn test_single_thread_is_deterministic() {
+        use crate::config::FileHook;
+        use std::sync::{Arc, Mutex};
+
+        let files: Vec<PathBuf> = ["tests/invalid2.ndjson", "tests/valid.ndjson", "tests/invalid1.ndjson"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let config = ValidatorConfig {
+            threads: Some(1),
+            pre_file_hook: Some(FileHook(Arc::new({
+                let started = Arc::clone(&started);
+                move |path: &Path| started.lock().unwrap().push(path.to_path_buf())
+            }))),
+            ..Default::default()
+        };
+
+        let first = validate_files_serde(&files, &config).unwrap();
+        let second = validate_files_serde(&files, &config).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.iter().map(|e| e.file_path.clone()).collect::<Vec<_>>(), [vec![files[0].clone(); 8], vec![files[2].clone()]].concat());
+
+        // With a single worker, files are even started in input order
+        let started = started.lock().unwrap();
+        assert_eq!(*started, [files.clone(), files].concat());
+    }
+
+    #[test]
+    fn test_threads_limits_pool_size() {
+        use crate::config::FileHook;
+        use std::sync::{Arc, Mutex};
+
+        let temp_dir = tempdir().unwrap();
+        let files: Vec<PathBuf> = (0..16)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("{}.ndjson", i));
+                fs::write(&path, "{\"a\": 1}\n{\"a\": }\n").unwrap();
+                path
+            })
+            .collect();
+        let pool_sizes = Arc::new(Mutex::new(Vec::new()));
+        let config = ValidatorConfig {
+            threads: Some(4),
+            pre_file_hook: Some(FileHook(Arc::new({
+                let pool_sizes = Arc::clone(&pool_sizes);
+                move |_: &Path| pool_sizes.lock().unwrap().push(rayon::current_num_threads())
+            }))),
+            ..Default::default()
+        };
+
+        let errors = validate_files_serde(&files, &config).unwrap();
+        assert_eq!(errors.len(), 16);
+        let pool_sizes = pool_sizes.lock().unwrap();
+        assert_eq!(pool_sizes.len(), 16);
+        assert!(pool_sizes.iter().all(|&size| size == 4));
+    }
+
+    #[test]
+    fn test_file_hooks() {
+        use crate::config::FileHook;
+        use std::sync::{Arc, Mutex};
+
+        let temp_dir = tempdir().unwrap();
+        let valid_path = temp_dir.path().join("valid.ndjson");
+        let invalid_path = temp_dir.path().join("invalid.ndjson");
+        fs::write(&valid_path, "{\"id\": 1}\n").unwrap();
+        fs::write(&invalid_path, "{\"id\": }\n{\"id\": 2}\n").unwrap();
+
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let config = ValidatorConfig {
+            pre_file_hook: Some(FileHook(Arc::new({
+                let started = Arc::clone(&started);
+                move |path: &Path| started.lock().unwrap().push(path.to_path_buf())
+            }))),
+            post_file_hook: Some(FileHook(Arc::new({
+                let finished = Arc::clone(&finished);
+                move |path: &Path, errors: &[ValidationError]| finished.lock().unwrap().push((path.to_path_buf(), errors.len()))
+            }))),
+            ..Default::default()
+        };
+
+        validate_files_serde(&[valid_path.clone(), invalid_path.clone()], &config).unwrap();
+
+        let mut started = started.lock().unwrap().clone();
+        started.sort();
+        assert_eq!(started, vec![invalid_path.clone(), valid_path.clone()]);
+
+        let mut finished = finished.lock().unwrap().clone();
+        finished.sort();
+        assert_eq!(finished, vec![(invalid_path, 1), (valid_path, 0)]);
+    }
+
+    #[test]
+    fn test_max_errors_per_file_still_cleans_every_invalid_line() {
+        let temp_dir = tempdir().unwrap();
+        let config = ValidatorConfig {
+            clean_files: true,
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            max_errors_per_file: Some(2),
+            ..Default::default()
+        };
+
+        let errors = process_file_serde(Path::new("tests/invalid2.ndjson"), &config).unwrap();
+        assert_eq!(errors.len(), 2);
+
+        // Only the first and last lines of invalid2.ndjson are valid
+        let content = fs::read_to_string(temp_dir.path().join("invalid2.ndjson")).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        // Reporting no errors at all gives the same result with and without cleaning
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(0),
+            ..config
+        };
+        assert!(process_file_serde(Path::new("tests/invalid2.ndjson"), &config).unwrap().is_empty());
+        assert!(process_file_serde(Path::new("tests/invalid2.ndjson"), &ValidatorConfig { clean_files: false, ..config }).unwrap().is_empty());
+    }
 }
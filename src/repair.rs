@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::validator::Backend;
+
+/// A kind of fix applied by `repair_file_serde`, in the order they are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixType {
+    /// Removed a comma directly before a closing `}` or `]`
+    TrailingComma,
+    /// Removed carriage returns, e.g. from Windows line endings
+    CrLf,
+    /// Removed a UTF-8 byte order mark
+    Bom,
+    /// Removed NUL bytes
+    NullByte,
+}
+
+/// A single fix applied to a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairAction {
+    pub line_number: usize,
+    pub fix_type: FixType,
+    /// The line before this fix
+    pub original: String,
+    /// The line after this fix
+    pub repaired: String,
+}
+
+/// What `repair_file_serde` did to a file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Invalid lines that became valid JSON after applying fixes
+    pub lines_fixed: usize,
+    /// Invalid lines that could not be fixed and were left out of the output
+    pub lines_removed: usize,
+    /// Every fix applied to the fixed lines
+    pub fixes: Vec<RepairAction>,
+}
+
+/// Writes a repaired copy of `input` to `output`.
+///
+/// Valid lines are copied as they are. Each invalid line has the fixes in `FixType` applied in
+/// order; if the result is valid JSON it is written in place of the original line, otherwise the
+/// line is removed. Unlike cleaning, which only removes lines, this recovers lines broken in common
+/// mechanical ways.
+pub fn repair_file_serde(input: &Path, output: &Path) -> Result<RepairReport> {
+    let mut reader = BufReader::new(File::open(input)?);
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut report = RepairReport::default();
+
+    let mut buf = Vec::new();
+    let mut line_number = 0;
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+        }
+
+        // Lines that are not UTF-8 are beyond repair
+        let Ok(line) = std::str::from_utf8(&buf) else {
+            report.lines_removed += 1;
+            continue;
+        };
+
+        if is_valid(line) {
+            writeln!(writer, "{}", line)?;
+            continue;
+        }
+
+        let mut actions = Vec::new();
+        let mut repaired = line.to_string();
+        for fix_type in [FixType::TrailingComma, FixType::CrLf, FixType::Bom, FixType::NullByte] {
+            let fixed = apply_fix(fix_type, &repaired);
+            if fixed != repaired {
+                actions.push(RepairAction {
+                    line_number,
+                    fix_type,
+                    original: repaired,
+                    repaired: fixed.clone(),
+                });
+                repaired = fixed;
+            }
+        }
+
+        if !actions.is_empty() && is_valid(&repaired) {
+            writeln!(writer, "{}", repaired)?;
+            report.lines_fixed += 1;
+            report.fixes.extend(actions);
+        } else {
+            report.lines_removed += 1;
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(report)
+}
+
+/// Blank lines count as valid, as they do when validating
+fn is_valid(line: &str) -> bool {
+    line.trim().is_empty() || Backend::Serde.parse_error(line).is_none()
+}
+
+fn apply_fix(fix_type: FixType, line: &str) -> String {
+    match fix_type {
+        FixType::TrailingComma => remove_trailing_commas(line),
+        FixType::CrLf => line.replace('\r', ""),
+        FixType::Bom => line.replace('\u{feff}', ""),
+        FixType::NullByte => line.replace('\0', ""),
+    }
+}
+
+/// Removes commas that are followed only by whitespace before a closing `}` or `]`, ignoring
+/// anything inside strings
+fn remove_trailing_commas(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut pending_comma: Option<usize> = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in line.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            result.push(c);
+            continue;
+        }
+
+        match c {
+            '}' | ']' => {
+                if let Some(position) = pending_comma.take() {
+                    result.remove(position);
+                }
+            }
+            ',' => {
+                pending_comma = Some(result.len());
+            }
+            c if c.is_whitespace() => {}
+            _ => pending_comma = None,
+        }
+        if c == '"' {
+            in_string = true;
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_remove_trailing_commas() {
+        assert_eq!(remove_trailing_commas("{\"a\": [1, 2, ], }"), "{\"a\": [1, 2 ] }");
+        assert_eq!(remove_trailing_commas("{\"a\": \",}\", \"b\": 1}"), "{\"a\": \",}\", \"b\": 1}");
+        assert_eq!(remove_trailing_commas("[1,,]"), "[1,]");
+    }
+
+    #[test]
+    fn test_repair_file() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("broken.ndjson");
+        let output = temp_dir.path().join("repaired.ndjson");
+        fs::write(
+            &input,
+            "\u{feff}{\"a\": 1}\r\n{\"b\": [1, 2,],}\n{\"c\": 3}\n{\"d\": \0 4}\n{\"e\": }\n",
+        )
+        .unwrap();
+
+        let report = repair_file_serde(&input, &output).unwrap();
+        assert_eq!(report.lines_fixed, 3);
+        assert_eq!(report.lines_removed, 1);
+
+        let fix_types: Vec<_> = report.fixes.iter().map(|fix| (fix.line_number, fix.fix_type)).collect();
+        assert_eq!(
+            fix_types,
+            [(1, FixType::CrLf), (1, FixType::Bom), (2, FixType::TrailingComma), (4, FixType::NullByte)]
+        );
+        assert_eq!(report.fixes[2].original, "{\"b\": [1, 2,],}");
+        assert_eq!(report.fixes[2].repaired, "{\"b\": [1, 2]}");
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(content, "{\"a\": 1}\n{\"b\": [1, 2]}\n{\"c\": 3}\n{\"d\":  4}\n");
+    }
+}
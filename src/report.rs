@@ -0,0 +1,386 @@
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::{ValidationError, ValidationSummary, ValidationWarning};
+
+/// Format used to render a `Report`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "lowercase"))]
+pub enum OutputFormat {
+    /// Plain text meant for people reading a terminal
+    #[default]
+    Human,
+    /// A single JSON document with the summary and all errors
+    Json,
+    /// One row per error, with a header row
+    Csv,
+    /// One JSON object per error, one per line
+    Ndjson,
+    /// A self-contained HTML page for viewing in a browser, see `to_html`
+    Html,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "human" | "text" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "html" => Ok(OutputFormat::Html),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
+}
+
+/// Result of validating a batch of files
+#[derive(Debug)]
+pub struct Report {
+    pub summary: ValidationSummary,
+    pub errors: Vec<ValidationError>,
+    /// Problems with whole files, such as those found by `ValidatorConfig::detect_single_line_array`
+    pub warnings: Vec<ValidationWarning>,
+    /// Format used by the `Display` implementation, taken from `ValidatorConfig::output_format`
+    pub format: OutputFormat,
+}
+
+impl Report {
+    /// Renders the summary and errors in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Human => self.render_human(),
+            OutputFormat::Json => self.render_json(),
+            OutputFormat::Csv => self.render_csv(),
+            OutputFormat::Ndjson => self.render_ndjson(),
+            OutputFormat::Html => to_html(self),
+        }
+    }
+
+    fn render_human(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Validation Summary:");
+        let _ = writeln!(out, "  Total files processed: {}", self.summary.total_files);
+        let _ = writeln!(out, "  Files with errors: {}", self.summary.files_with_errors);
+        let _ = writeln!(out, "  Total errors found: {}", self.summary.total_errors);
+        if self.summary.partial_scan {
+            let _ = writeln!(out, "  Partial scan: only part of each file was validated");
+        }
+        if self.summary.truncated {
+            let _ = writeln!(out, "  Truncated: an error limit was reached, so there may be more errors");
+        }
+
+        for warning in &self.warnings {
+            let _ = writeln!(out, "warning: {}: {}", warning.file_path.display(), warning.message);
+        }
+
+        for error in &self.errors {
+            let _ = writeln!(out, "{}:{}: {}", error.file_path.display(), error.line_number, error.error);
+            let _ = writeln!(out, "    {}", error.line_content);
+        }
+
+        out
+    }
+
+    fn render_json(&self) -> String {
+        let errors: Vec<_> = self.errors.iter().map(error_json).collect();
+        let warnings: Vec<_> = self
+            .warnings
+            .iter()
+            .map(|warning| json!({ "file_path": warning.file_path.display().to_string(), "message": warning.message }))
+            .collect();
+        json!({
+            "summary": summary_json(&self.summary),
+            "errors": errors,
+            "warnings": warnings,
+        })
+        .to_string()
+    }
+
+    fn render_csv(&self) -> String {
+        let mut out = String::from("file_path,line_number,line_content,error\n");
+        for error in &self.errors {
+            let _ = writeln!(
+                out,
+                "{},{},{},{}",
+                csv_field(&error.file_path.display().to_string()),
+                error.line_number,
+                csv_field(&error.line_content),
+                csv_field(&error.error)
+            );
+        }
+        out
+    }
+
+    fn render_ndjson(&self) -> String {
+        let mut out = String::new();
+        for error in &self.errors {
+            let _ = writeln!(out, "{}", error_json(error));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(self.format))
+    }
+}
+
+/// Script that makes the error table sortable by clicking a column header and filterable by the
+/// text in the filter box
+const HTML_SCRIPT: &str = r#"
+const table = document.getElementById("errors");
+const rows = () => Array.from(table.tBodies[0].rows);
+document.getElementById("filter").addEventListener("input", (event) => {
+  const needle = event.target.value.toLowerCase();
+  for (const row of rows()) {
+    row.hidden = !row.textContent.toLowerCase().includes(needle);
+  }
+});
+table.querySelectorAll("th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const ascending = th.dataset.order !== "asc";
+    table.querySelectorAll("th").forEach((other) => delete other.dataset.order);
+    th.dataset.order = ascending ? "asc" : "desc";
+    const key = (row) => row.cells[column].dataset.sort ?? row.cells[column].textContent;
+    const sorted = rows().sort((a, b) => {
+      const [x, y] = [key(a), key(b)];
+      const order = isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y;
+      return ascending ? order : -order;
+    });
+    table.tBodies[0].append(...sorted);
+  });
+});
+"#;
+
+const HTML_STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+#errors th { cursor: pointer; background: #f0f0f0; }
+#errors th[data-order="asc"]::after { content: " \25B2"; }
+#errors th[data-order="desc"]::after { content: " \25BC"; }
+code { white-space: pre-wrap; word-break: break-all; }
+mark { background: #f88; }
+"#;
+
+/// Renders a report as a self-contained HTML page with a summary table, any warnings, and a
+/// sortable, filterable table of errors. The position at which the parser gave up is highlighted
+/// in each invalid line.
+pub fn to_html(report: &Report) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ND-JSON Validation Report</title>");
+    let _ = writeln!(out, "<style>{}</style>\n</head>\n<body>", HTML_STYLE);
+    let _ = writeln!(out, "<h1>ND-JSON Validation Report</h1>");
+
+    let _ = writeln!(out, "<table id=\"summary\">");
+    let _ = writeln!(out, "<tr><th>Total files processed</th><td>{}</td></tr>", report.summary.total_files);
+    let _ = writeln!(out, "<tr><th>Files with errors</th><td>{}</td></tr>", report.summary.files_with_errors);
+    let _ = writeln!(out, "<tr><th>Total errors found</th><td>{}</td></tr>", report.summary.total_errors);
+    let _ = writeln!(out, "</table>");
+
+    if !report.warnings.is_empty() {
+        let _ = writeln!(out, "<h2>Warnings</h2>\n<ul>");
+        for warning in &report.warnings {
+            let _ = writeln!(
+                out,
+                "<li>{}: {}</li>",
+                escape_html(&warning.file_path.display().to_string()),
+                escape_html(&warning.message)
+            );
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+
+    let _ = writeln!(out, "<h2>Errors</h2>");
+    let _ = writeln!(out, "<input id=\"filter\" type=\"search\" placeholder=\"Filter errors\">");
+    let _ = writeln!(out, "<table id=\"errors\">");
+    let _ = writeln!(out, "<thead><tr><th>File</th><th>Line</th><th>Error</th><th>Content</th></tr></thead>\n<tbody>");
+    for error in &report.errors {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td data-sort=\"{}\">{}</td><td>{}</td><td><code>{}</code></td></tr>",
+            escape_html(&error.file_path.display().to_string()),
+            error.line_number,
+            error.line_number,
+            escape_html(&error.error),
+            highlighted_line(error)
+        );
+    }
+    let _ = writeln!(out, "</tbody>\n</table>");
+
+    let _ = writeln!(out, "<script>{}</script>\n</body>\n</html>", HTML_SCRIPT);
+    out
+}
+
+/// The escaped line content with the character at which the parser gave up wrapped in `<mark>`.
+/// Without a known position the line is shown as is.
+fn highlighted_line(error: &ValidationError) -> String {
+    let line = &error.line_content;
+    // `sonic_error_offset` is a byte offset, `column_number` is 1-based
+    let position = error
+        .sonic_error_offset
+        .or(error.column_number.map(|column| column.saturating_sub(1)));
+
+    let Some(mut start) = position.filter(|&position| position < line.len()) else {
+        return escape_html(line);
+    };
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let end = line[start..].chars().next().map_or(line.len(), |c| start + c.len_utf8());
+
+    format!(
+        "{}<mark>{}</mark>{}",
+        escape_html(&line[..start]),
+        escape_html(&line[start..end]),
+        escape_html(&line[end..])
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// JSON object for a summary, as used by the JSON format and the cleaning summary file
+pub(crate) fn summary_json(summary: &ValidationSummary) -> serde_json::Value {
+    json!({
+        "total_files": summary.total_files,
+        "files_with_errors": summary.files_with_errors,
+        "total_errors": summary.total_errors,
+        "partial_scan": summary.partial_scan,
+        "truncated": summary.truncated,
+    })
+}
+
+/// JSON object for an error, as used by the JSON and ND-JSON formats
+#[cfg(feature = "serde")]
+pub(crate) fn error_json(error: &ValidationError) -> serde_json::Value {
+    serde_json::to_value(error).unwrap_or_else(|_| {
+        // Only a path that isn't valid UTF-8 fails to serialize, so it is shown lossily instead
+        let lossy = ValidationError {
+            file_path: error.file_path.to_string_lossy().into_owned().into(),
+            ..error.clone()
+        };
+        serde_json::to_value(lossy).expect("an error with a UTF-8 path serializes")
+    })
+}
+
+/// JSON object for an error, with the same fields as the `serde` feature's `Serialize` impl
+#[cfg(not(feature = "serde"))]
+pub(crate) fn error_json(error: &ValidationError) -> serde_json::Value {
+    json!({
+        "file_path": error.file_path.display().to_string(),
+        "line_number": error.line_number,
+        "line_content": error.line_content,
+        "error": error.error,
+        "line_hash": error.line_hash,
+        "column_number": error.column_number,
+        "sonic_error_offset": error.sonic_error_offset,
+    })
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn report() -> Report {
+        Report {
+            summary: ValidationSummary {
+                total_files: 2,
+                files_with_errors: 1,
+                total_errors: 1,
+                partial_scan: false,
+                truncated: false,
+            },
+            errors: vec![ValidationError {
+                file_path: PathBuf::from("data.ndjson"),
+                line_number: 3,
+                line_content: "{\"a\": }".to_string(),
+                error: "expected value at line 1 column 7".to_string(),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
+            }],
+            warnings: vec![ValidationWarning {
+                file_path: PathBuf::from("array.ndjson"),
+                line_number: Some(1),
+                message: "the file is a single JSON array".to_string(),
+            }],
+            format: OutputFormat::Json,
+        }
+    }
+
+    #[test]
+    fn test_render_json() {
+        let value: serde_json::Value = serde_json::from_str(&report().render(OutputFormat::Json)).unwrap();
+        assert_eq!(value["summary"]["total_files"], 2);
+        assert_eq!(value["errors"][0]["line_number"], 3);
+        assert_eq!(value["errors"][0]["line_content"], "{\"a\": }");
+        assert_eq!(value["warnings"][0]["file_path"], "array.ndjson");
+    }
+
+    #[test]
+    fn test_to_html() {
+        let mut report = report();
+        report.errors[0].column_number = Some(7);
+        report.errors[0].line_content = "{\"a\": <}".to_string();
+
+        let html = to_html(&report);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<tr><th>Total files processed</th><td>2</td></tr>"));
+        assert!(html.contains("<code>{&quot;a&quot;: <mark>&lt;</mark>}</code>"));
+        assert!(html.contains("<li>array.ndjson: the file is a single JSON array</li>"));
+        assert_eq!(report.render(OutputFormat::Html), html);
+        assert_eq!("HTML".parse::<OutputFormat>(), Ok(OutputFormat::Html));
+    }
+
+    #[test]
+    fn test_render_csv_quotes_fields() {
+        let csv = report().render(OutputFormat::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("file_path,line_number,line_content,error"));
+        assert_eq!(lines.next(), Some("data.ndjson,3,\"{\"\"a\"\": }\",expected value at line 1 column 7"));
+    }
+
+    #[test]
+    fn test_render_ndjson_and_display() {
+        let report = report();
+        let ndjson = report.render(OutputFormat::Ndjson);
+        assert_eq!(ndjson.lines().count(), 1);
+        assert!(serde_json::from_str::<serde_json::Value>(ndjson.trim_end()).is_ok());
+
+        // Display uses the format the report was created with
+        assert_eq!(report.to_string(), report.render(OutputFormat::Json));
+        assert!(report.render(OutputFormat::Human).contains("data.ndjson:3: expected value"));
+        assert!(report.render(OutputFormat::Human).contains("warning: array.ndjson: the file is a single JSON array"));
+    }
+}
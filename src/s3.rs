@@ -0,0 +1,87 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::Client;
+
+use crate::cleaner::clean_reader;
+use crate::config::ValidatorConfig;
+use crate::error::{NdJsonError, Result};
+use crate::processor::{is_ndjson_path, summarize};
+use crate::report::Report;
+use crate::validator::{find_warnings, promote_warnings, scan_reader, Backend};
+
+/// Validates every ND-JSON object under `prefix` in an S3 bucket.
+///
+/// Credentials and region are loaded the same way as the AWS CLI does. Objects are downloaded one
+/// at a time and errors are reported against `s3://<bucket>/<key>`. When cleaning is enabled,
+/// cleaned objects are written to the output directory under their file name.
+///
+/// Must be called from within a Tokio runtime.
+pub async fn validate_s3_prefix(bucket: &str, prefix: &str, config: &ValidatorConfig) -> Result<Report> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let client = Client::new(&sdk_config);
+
+    let mut total_files = 0;
+    let mut all_errors = Vec::new();
+    let mut all_warnings = Vec::new();
+    let mut truncated = false;
+
+    let mut pages = client
+        .list_objects_v2()
+        .bucket(bucket)
+        .prefix(prefix)
+        .into_paginator()
+        .send();
+
+    while let Some(page) = pages.next().await {
+        let page = page.map_err(s3_error)?;
+
+        for object in page.contents() {
+            let Some(key) = object.key() else { continue };
+            if !is_ndjson_path(Path::new(key)) {
+                continue;
+            }
+
+            let response = client.get_object().bucket(bucket).key(key).send().await.map_err(s3_error)?;
+            let content = response.body.collect().await.map_err(s3_error)?.into_bytes();
+
+            let label = PathBuf::from(format!("s3://{}/{}", bucket, key));
+            let scan = scan_reader(content.as_ref(), &label, config, Backend::Serde, config.error_limit_per_file())?;
+            let mut errors = scan.errors;
+            truncated |= scan.truncated;
+            let mut warnings = find_warnings(content.as_ref(), &label, config)?;
+
+            if let Some(output_dir) = config.output_dir.as_ref().filter(|_| config.clean_files) {
+                fs::create_dir_all(output_dir)
+                    .map_err(|_| NdJsonError::FailedToCreateOutputDir(output_dir.display().to_string()))?;
+
+                let output_path = output_dir.join(Path::new(key).file_name().unwrap_or_default());
+                clean_reader(content.as_ref(), &output_path, &errors, config)?;
+            }
+
+            // Only after cleaning, since the lines warned about are valid and must be kept
+            promote_warnings(&mut errors, &mut warnings, config);
+            all_warnings.extend(warnings);
+
+            total_files += 1;
+            all_errors.extend(errors);
+        }
+    }
+
+    if total_files == 0 {
+        return Err(NdJsonError::NoFilesFound(format!("s3://{}/{}", bucket, prefix)));
+    }
+
+    Ok(Report {
+        summary: summarize(total_files, &all_errors, truncated, config),
+        errors: all_errors,
+        warnings: all_warnings,
+        format: config.output_format,
+    })
+}
+
+fn s3_error(e: impl std::error::Error + Send + Sync + 'static) -> NdJsonError {
+    NdJsonError::Io(io::Error::other(e))
+}
@@ -0,0 +1,91 @@
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use axum::body::Bytes;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::json;
+
+use crate::config::ValidatorConfig;
+use crate::error::Result;
+use crate::validator::{check_line, validate_reader, Backend, Lines};
+
+/// Synthetic file path used in errors for request bodies
+pub const REQUEST_LABEL: &str = "<request>";
+
+/// Content type of ND-JSON request and response bodies
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Builds the router with the `POST /validate` and `POST /clean` endpoints
+pub fn router() -> Router {
+    Router::new()
+        .route("/validate", post(validate))
+        .route("/clean", post(clean))
+}
+
+/// Serves the validation endpoints on all interfaces on `port` until the process is stopped
+pub async fn serve(port: u16) -> io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+    axum::serve(listener, router()).await
+}
+
+/// Validates an ND-JSON body, responding with a JSON object listing the invalid lines
+async fn validate(body: Bytes) -> Response {
+    let config = ValidatorConfig::default();
+    let errors = match validate_reader(body.as_ref(), Path::new(REQUEST_LABEL), &config, Backend::Serde) {
+        Ok(errors) => errors,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    let error_list: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            json!({
+                "line_number": error.line_number,
+                "line_content": error.line_content,
+                "error": error.error,
+            })
+        })
+        .collect();
+
+    Json(json!({
+        "valid": errors.is_empty(),
+        "total_errors": errors.len(),
+        "errors": error_list,
+    }))
+    .into_response()
+}
+
+/// Responds with the valid lines of an ND-JSON body
+async fn clean(body: Bytes) -> Response {
+    match valid_lines(body.as_ref()) {
+        Ok(cleaned) => ([(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)], cleaned).into_response(),
+        Err(e) => bad_request(e.to_string()),
+    }
+}
+
+fn valid_lines(body: &[u8]) -> Result<String> {
+    let config = ValidatorConfig::default();
+    let label = Path::new(REQUEST_LABEL);
+    let mut cleaned = String::new();
+
+    for line in Lines::new(body, label) {
+        let line = line?;
+        if line.content.trim().is_empty() {
+            continue;
+        }
+        if check_line(&line.content, line.number, label, &config, Backend::Serde).is_none() {
+            cleaned.push_str(&line.content);
+            cleaned.push('\n');
+        }
+    }
+
+    Ok(cleaned)
+}
+
+fn bad_request(message: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": message }))).into_response()
+}
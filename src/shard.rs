@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use crate::error::{NdJsonError, Result};
+
+/// Splits a shared file list between independent validator processes.
+///
+/// Every process is given the same file list and a different `shard_index`; together the shards
+/// cover each file exactly once. The per-shard `ValidationSummary` results can then be combined
+/// with [`ValidationSummary::merge`](crate::ValidationSummary::merge).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardedValidator {
+    pub shard_index: usize,
+    pub total_shards: usize,
+}
+
+impl ShardedValidator {
+    /// Returns an error unless `shard_index < total_shards`
+    pub fn new(shard_index: usize, total_shards: usize) -> Result<Self> {
+        if shard_index >= total_shards {
+            return Err(NdJsonError::InvalidConfig(format!(
+                "shard index {} is out of range for {} shards",
+                shard_index, total_shards
+            )));
+        }
+        Ok(ShardedValidator { shard_index, total_shards })
+    }
+
+    /// Selects every `total_shards`-th file, starting at `shard_index`.
+    ///
+    /// The files of a shard are not contiguous in `files`, so they are returned as references
+    /// rather than a subslice.
+    pub fn files_for_shard<'a>(&self, files: &'a [PathBuf]) -> Vec<&'a PathBuf> {
+        files.iter().skip(self.shard_index).step_by(self.total_shards.max(1)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_cover_every_file_once() {
+        let files: Vec<PathBuf> = (0..7).map(|i| PathBuf::from(format!("{}.ndjson", i))).collect();
+
+        let first = ShardedValidator::new(0, 3).unwrap().files_for_shard(&files);
+        assert_eq!(first, vec![&files[0], &files[3], &files[6]]);
+
+        let mut covered: Vec<&PathBuf> = (0..3)
+            .flat_map(|index| ShardedValidator::new(index, 3).unwrap().files_for_shard(&files))
+            .collect();
+        covered.sort();
+        assert_eq!(covered, files.iter().collect::<Vec<_>>());
+
+        assert!(matches!(ShardedValidator::new(3, 3), Err(NdJsonError::InvalidConfig(_))));
+    }
+}
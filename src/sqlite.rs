@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::error::{Result, ValidationError};
+
+/// Appends `errors` to the `validation_errors` table of the SQLite database at `path`, creating
+/// the database and table if needed
+pub(crate) fn write_error_log(path: &Path, errors: &[ValidationError]) -> Result<()> {
+    let mut connection = Connection::open(path)?;
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS validation_errors (
+            file_path TEXT,
+            line_number INTEGER,
+            error TEXT,
+            line_content TEXT
+        )",
+        [],
+    )?;
+
+    // A single transaction, since committing every insert separately is very slow
+    let transaction = connection.transaction()?;
+    {
+        let mut insert = transaction.prepare(
+            "INSERT INTO validation_errors (file_path, line_number, error, line_content) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for error in errors {
+            insert.execute(params![
+                error.file_path.to_string_lossy(),
+                error.line_number as i64,
+                error.error,
+                error.line_content,
+            ])?;
+        }
+    }
+    transaction.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ValidatorConfig;
+    use crate::processor::validate_files_with_summary_serde;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sqlite_error_log() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("input.ndjson");
+        fs::write(&input, "{\"a\": 1}\n{\"a\": }\n[1,\n").unwrap();
+        let log_path = temp_dir.path().join("errors.db");
+        let config = ValidatorConfig {
+            sqlite_error_log: Some(log_path.clone()),
+            ..Default::default()
+        };
+
+        // Running twice appends to the same table
+        let files = vec![input.clone()];
+        validate_files_with_summary_serde(&files, &config).unwrap();
+        validate_files_with_summary_serde(&files, &config).unwrap();
+
+        let connection = Connection::open(&log_path).unwrap();
+        let rows: Vec<(String, i64, String)> = connection
+            .prepare("SELECT file_path, line_number, line_content FROM validation_errors ORDER BY rowid")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], (input.display().to_string(), 2, "{\"a\": }".to_string()));
+        assert_eq!(rows[1].1, 3);
+    }
+}
@@ -0,0 +1,162 @@
+use std::mem;
+use std::path::PathBuf;
+
+use crate::config::ValidatorConfig;
+use crate::error::ValidationError;
+use crate::validator::{reported_line_content, Backend, LineChecker};
+
+/// Validates ND-JSON that arrives in chunks, e.g. from a WebSocket or a chunked HTTP body.
+///
+/// Chunks may split lines anywhere, including inside a multi-byte UTF-8 character. Each complete
+/// line is validated as soon as its terminator arrives; the trailing partial line is buffered
+/// until the next chunk or `finish`. Lines outside `ValidatorConfig::line_range` are counted but
+/// not checked.
+pub struct StreamingValidator {
+    buffer: Vec<u8>,
+    errors: Vec<ValidationError>,
+    line_number: usize,
+    file_path: PathBuf,
+    config: ValidatorConfig,
+    checker: LineChecker,
+}
+
+impl StreamingValidator {
+    /// Creates a validator that reports errors against `label`
+    pub fn new(label: &str) -> Self {
+        Self::with_config(label, ValidatorConfig::default())
+    }
+
+    /// Creates a validator that honours the line handling options in `config`
+    pub fn with_config(label: &str, config: ValidatorConfig) -> Self {
+        Self {
+            buffer: Vec::new(),
+            errors: Vec::new(),
+            line_number: 0,
+            file_path: PathBuf::from(label),
+            checker: LineChecker::new(&config),
+            config,
+        }
+    }
+
+    /// Adds a chunk of input, returning the errors for the lines it completed
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> Vec<ValidationError> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..].iter().position(|&b| b == b'\n') {
+            let line_end = start + end;
+            self.check(start, line_end);
+            start = line_end + 1;
+        }
+        self.buffer.drain(..start);
+
+        mem::take(&mut self.errors)
+    }
+
+    /// Validates any buffered data as the final line, returning its error if it is invalid
+    pub fn finish(&mut self) -> Vec<ValidationError> {
+        if !self.buffer.is_empty() {
+            self.check(0, self.buffer.len());
+            self.buffer.clear();
+        }
+
+        mem::take(&mut self.errors)
+    }
+
+    /// Validates `buffer[start..end]`, which holds one line without its `\n`
+    fn check(&mut self, start: usize, end: usize) {
+        self.line_number += 1;
+
+        let mut bytes = &self.buffer[start..end];
+        if let Some(stripped) = bytes.strip_suffix(b"\r") {
+            bytes = stripped;
+        }
+
+        let in_range = self.config.line_range.as_ref().is_none_or(|range| range.contains(&self.line_number));
+        let error = match std::str::from_utf8(bytes) {
+            Ok(line) if !in_range => {
+                // Still needed to spot a schema header that comes before the range
+                self.checker.is_schema_header(line, self.line_number, &self.config);
+                None
+            }
+            _ if !in_range => None,
+            Ok(line) => self.checker.check(line, self.line_number, &self.file_path, &self.config, Backend::Serde),
+            Err(e) => Some(ValidationError {
+                file_path: self.file_path.clone(),
+                line_number: self.line_number,
+                line_content: reported_line_content(bytes, &self.config),
+                error: format!("invalid UTF-8: {}", e),
+                line_hash: None,
+                column_number: None,
+                sonic_error_offset: None,
+            }),
+        };
+
+        self.errors.extend(error);
+    }
+}
+
+/// Validates ND-JSON delivered as a sequence of chunks, returning all errors once the input ends
+pub fn validate_ndjson_bytes_streaming<I>(chunks: I, label: &str) -> Vec<ValidationError>
+where
+    I: IntoIterator<Item = Vec<u8>>,
+{
+    let mut validator = StreamingValidator::new(label);
+    let mut errors = Vec::new();
+
+    for chunk in chunks {
+        errors.extend(validator.push_chunk(&chunk));
+    }
+    errors.extend(validator.finish());
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_split_across_chunks() {
+        let mut validator = StreamingValidator::new("stream");
+
+        assert!(validator.push_chunk(b"{\"a\": 1}\n{\"b\"").is_empty());
+
+        let errors = validator.push_chunk(b": }\r\n{\"c\": 3}\n[1,");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 2);
+        assert_eq!(errors[0].line_content, "{\"b\": }");
+
+        // The unterminated last line is only checked once the input ends
+        let errors = validator.finish();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 4);
+        assert!(validator.finish().is_empty());
+    }
+
+    #[test]
+    fn test_line_range() {
+        let config = ValidatorConfig {
+            line_range: Some(2..4),
+            ..Default::default()
+        };
+        let mut validator = StreamingValidator::with_config("stream", config);
+
+        let errors = validator.push_chunk(b"{\"a\": }\n{\"a\": }\n\xff\n{\"a\": }");
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(validator.finish().is_empty());
+    }
+
+    #[test]
+    fn test_multibyte_character_split_across_chunks() {
+        let line = "{\"name\": \"Zoë\"}\n".as_bytes();
+        let split = line.iter().position(|&b| b >= 0x80).unwrap() + 1;
+
+        let chunks = vec![line[..split].to_vec(), line[split..].to_vec(), b"\xff\n".to_vec()];
+        let errors = validate_ndjson_bytes_streaming(chunks, "stream");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 2);
+        assert!(errors[0].error.starts_with("invalid UTF-8"));
+    }
+}
@@ -1,72 +1,770 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sonic_rs::LazyValue;
 
-use crate::error::{Result, ValidationError};
+use crate::config::ValidatorConfig;
+use crate::duplicate_keys::find_duplicate_key;
+use crate::error::{NdJsonError, Result, ValidationError, ValidationWarning};
+use crate::float_precision::find_imprecise_float;
 
-/// Validates a single ND-JSON file and returns a list of validation errors
-pub fn validate_file_serde(file_path: &Path) -> Result<Vec<ValidationError>> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
+/// JSON parser used to check each line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Backend {
+    Serde,
+    Sonic,
+}
+
+/// Why a line failed to parse, with whatever position information the backend provides
+pub(crate) struct ParseError {
+    pub message: String,
+    pub column_number: Option<usize>,
+    pub sonic_error_offset: Option<usize>,
+}
+
+impl ParseError {
+    pub(crate) fn without_position(message: String) -> Self {
+        Self {
+            message,
+            column_number: None,
+            sonic_error_offset: None,
+        }
+    }
+}
+
+impl Backend {
+    /// Parses a single line, returning the error if it is not valid JSON
+    pub(crate) fn parse_error(self, line: &str) -> Option<ParseError> {
+        match self {
+            Backend::Serde => serde_json::from_str::<Value>(line).err().map(|e| ParseError {
+                message: e.to_string(),
+                column_number: Some(e.column()),
+                sonic_error_offset: None,
+            }),
+            Backend::Sonic => sonic_rs::from_str::<LazyValue>(line).err().map(|e| ParseError {
+                message: e.to_string(),
+                column_number: Some(e.column()),
+                sonic_error_offset: Some(e.offset()),
+            }),
+        }
+    }
+}
+
+/// A single line read from an ND-JSON source, without its line terminator
+pub(crate) struct Line {
+    /// 1-based line number
+    pub number: usize,
+    /// Offset of the first byte of the line in the source
+    pub byte_offset: u64,
+    pub content: String,
+    /// Terminator that was stripped from the line, or `None` for a last line without one
+    pub ending: Option<LineEnding>,
+}
+
+/// Line terminator style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    fn escaped(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\\n",
+            LineEnding::CrLf => "\\r\\n",
+        }
+    }
+}
+
+/// Iterator over the lines of a reader that keeps track of byte offsets.
+///
+/// Unlike `BufRead::lines`, non-UTF-8 input is reported as `NdJsonError::UnsupportedEncoding`
+/// with the offset of the first offending byte, unless the Latin-1 fallback is enabled.
+pub(crate) struct Lines<R> {
+    reader: R,
+    file_path: PathBuf,
+    line_number: usize,
+    byte_offset: u64,
+    buf: Vec<u8>,
+    latin1_fallback: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub(crate) fn new(reader: R, file_path: &Path) -> Self {
+        Self {
+            reader,
+            file_path: file_path.to_path_buf(),
+            line_number: 0,
+            byte_offset: 0,
+            buf: Vec::new(),
+            latin1_fallback: false,
+        }
+    }
+
+    /// Decodes lines that are not valid UTF-8 as Latin-1 instead of failing, when `enabled`
+    pub(crate) fn with_latin1_fallback(mut self, enabled: bool) -> Self {
+        self.latin1_fallback = enabled;
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Line>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        let bytes_read = match self.reader.read_until(b'\n', &mut self.buf) {
+            Ok(0) => return None,
+            Ok(n) => n,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        let byte_offset = self.byte_offset;
+        self.byte_offset += bytes_read as u64;
+        self.line_number += 1;
+
+        // Strip the terminator the same way `BufRead::lines` does
+        let mut ending = None;
+        if self.buf.last() == Some(&b'\n') {
+            self.buf.pop();
+            ending = Some(LineEnding::Lf);
+            if self.buf.last() == Some(&b'\r') {
+                self.buf.pop();
+                ending = Some(LineEnding::CrLf);
+            }
+        }
+
+        let content = match String::from_utf8(std::mem::take(&mut self.buf)) {
+            Ok(content) => content,
+            // Every Latin-1 byte is the code point of the same value
+            Err(e) if self.latin1_fallback => e.into_bytes().into_iter().map(char::from).collect(),
+            Err(e) => {
+                return Some(Err(NdJsonError::UnsupportedEncoding {
+                    path: self.file_path.clone(),
+                    byte_offset: byte_offset + e.utf8_error().valid_up_to() as u64,
+                }))
+            }
+        };
+
+        Some(Ok(Line {
+            number: self.line_number,
+            byte_offset,
+            content,
+            ending,
+        }))
+    }
+}
+
+/// Errors found while scanning a reader, along with how many lines were read to find them
+pub(crate) struct Scan {
+    pub errors: Vec<ValidationError>,
+    pub lines_scanned: usize,
+    /// Whether errors past the error limit were left out
+    pub truncated: bool,
+}
+
+/// Validates ND-JSON read from `reader`, reporting errors against `file_path`
+pub(crate) fn validate_reader(
+    reader: impl BufRead,
+    file_path: &Path,
+    config: &ValidatorConfig,
+    backend: Backend,
+) -> Result<Vec<ValidationError>> {
+    scan_reader(reader, file_path, config, backend, config.error_limit_per_file()).map(|scan| scan.errors)
+}
+
+/// Like `validate_reader`, but stops reading once `error_limit` errors have been found and also
+/// reports the number of lines read
+pub(crate) fn scan_reader(
+    reader: impl BufRead,
+    file_path: &Path,
+    config: &ValidatorConfig,
+    backend: Backend,
+    error_limit: Option<usize>,
+) -> Result<Scan> {
     let mut errors = Vec::new();
+    let (lines_scanned, truncated) = for_each_error(reader, file_path, config, backend, error_limit, |error| {
+        errors.push(error);
+        Ok(())
+    })?;
 
-    for (i, line_result) in reader.lines().enumerate() {
-        let line_number = i + 1;
-        let line = line_result?;
-        
-        if line.trim().is_empty() {
+    Ok(Scan { errors, lines_scanned, truncated })
+}
+
+/// Like `scan_reader`, but hands each error to `on_error` as soon as it is found instead of
+/// collecting them. Returns the number of lines read and whether an error past `error_limit` was
+/// found and left out.
+pub(crate) fn for_each_error(
+    reader: impl BufRead,
+    file_path: &Path,
+    config: &ValidatorConfig,
+    backend: Backend,
+    error_limit: Option<usize>,
+    mut on_error: impl FnMut(ValidationError) -> Result<()>,
+) -> Result<(usize, bool)> {
+    let mut errors_found = 0;
+    let mut lines_scanned = 0;
+    let mut checker = LineChecker::new(config);
+
+    for line in Lines::new(reader, file_path).with_latin1_fallback(config.encoding_fallback) {
+        let line = line?;
+        if let Some(range) = &config.line_range {
+            if line.number >= range.end {
+                break;
+            }
+            if line.number < range.start {
+                // Still needed to spot a schema header that comes before the range
+                checker.is_schema_header(&line.content, line.number, config);
+                continue;
+            }
+        }
+        lines_scanned += 1;
+        // Not a validated line, so it doesn't end a `first_line_only` scan
+        if checker.is_schema_header(&line.content, line.number, config) {
             continue;
         }
-        
-        match serde_json::from_str::<Value>(&line) {
-            Ok(_) => {}
-            Err(e) => {
-                errors.push(ValidationError {
+
+        if let Some(error) = checker.check(&line.content, line.number, file_path, config, backend) {
+            // Checked before reporting, so that a limit of 0 reports nothing
+            if error_limit.is_some_and(|limit| errors_found >= limit) {
+                return Ok((lines_scanned, true));
+            }
+            on_error(error)?;
+            errors_found += 1;
+        }
+        if config.first_line_only && !is_skipped(&line.content, line.number, config) {
+            break;
+        }
+    }
+
+    Ok((lines_scanned, false))
+}
+
+/// Checks an input as a whole for the problems enabled in `config` that are reported as warnings
+/// rather than errors. Nothing is read if none are enabled.
+pub(crate) fn find_warnings(reader: impl BufRead, file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationWarning>> {
+    if !has_warning_checks(config) {
+        return Ok(Vec::new());
+    }
+    let checks_every_line = config.detect_compressed_lines || config.strict_unicode;
+
+    let mut warnings = Vec::new();
+    // Only the first two lines with content are needed to tell whether there is exactly one
+    let mut content_lines = Vec::new();
+    let mut first_ending = None;
+    let mut mixed_endings_found = false;
+
+    for line in Lines::new(reader, file_path).with_latin1_fallback(config.encoding_fallback) {
+        let line = line?;
+        if config.detect_single_line_array && content_lines.len() < 2 && !is_skipped(&line.content, line.number, config) {
+            content_lines.push((line.number, line.content.clone()));
+        }
+
+        if config.check_newline_consistency && !mixed_endings_found {
+            match (first_ending, line.ending) {
+                (None, ending) => first_ending = ending,
+                (Some(first), Some(ending)) if ending != first => {
+                    mixed_endings_found = true;
+                    warnings.push(ValidationWarning {
+                        file_path: file_path.to_path_buf(),
+                        line_number: Some(line.number),
+                        message: format!(
+                            "line {} ends with {} (at byte {}), but the first line ends with {}; \
+                             the file mixes newline styles",
+                            line.number,
+                            ending.escaped(),
+                            line.byte_offset + line.content.len() as u64,
+                            first.escaped()
+                        ),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if config.detect_compressed_lines {
+            if let Some(kind) = compressed_kind(&line.content) {
+                warnings.push(ValidationWarning {
+                    file_path: file_path.to_path_buf(),
+                    line_number: Some(line.number),
+                    message: format!(
+                        "line {} looks like {} rather than JSON; decompress it before validating",
+                        line.number, kind
+                    ),
+                });
+            }
+        }
+
+        if config.strict_unicode && !is_skipped(&line.content, line.number, config) {
+            if let Some((escape, character)) = unnecessary_unicode_escape(&line.content) {
+                warnings.push(ValidationWarning {
                     file_path: file_path.to_path_buf(),
-                    line_number,
-                    line_content: line,
-                    error: e.to_string(),
+                    line_number: Some(line.number),
+                    message: format!(
+                        "line {} escapes '{}' as {}, which doesn't need escaping",
+                        line.number, character, escape
+                    ),
                 });
             }
         }
+
+        let arrays_done = !config.detect_single_line_array || content_lines.len() > 1;
+        let endings_done = !config.check_newline_consistency || mixed_endings_found;
+        if arrays_done && endings_done && !checks_every_line {
+            break;
+        }
     }
 
-    Ok(errors)
+    let is_array = |line: &str| {
+        line.trim_start().starts_with('[') && serde_json::from_str::<serde::de::IgnoredAny>(line).is_ok()
+    };
+    if let [(line_number, line)] = content_lines.as_slice() {
+        if is_array(line) {
+            warnings.push(ValidationWarning {
+                file_path: file_path.to_path_buf(),
+                line_number: Some(*line_number),
+                message: "the file is a single JSON array rather than one value per line; \
+                          convert it with `json_array_to_ndjson`"
+                    .to_string(),
+            });
+        }
+    }
+
+    Ok(warnings)
 }
 
-/// Validates a single ND-JSON file using sonic-rs and returns a list of validation errors
-pub fn validate_file_sonic(file_path: &Path) -> Result<Vec<ValidationError>> {
+/// Whether any of the checks done by `find_warnings` are enabled
+pub(crate) fn has_warning_checks(config: &ValidatorConfig) -> bool {
+    config.detect_single_line_array
+        || config.check_newline_consistency
+        || config.detect_compressed_lines
+        || config.strict_unicode
+}
+
+/// Describes the compressed data a line seems to hold, if any
+fn compressed_kind(line: &str) -> Option<&'static str> {
+    // The gzip magic bytes 1f 8b, as decoded by the Latin-1 fallback
+    if line.starts_with("\u{1f}\u{8b}") {
+        return Some("gzip data");
+    }
+
+    // Base64 of the magic bytes followed by the deflate method byte 08
+    let line = line.trim();
+    let is_base64 = line.len().is_multiple_of(4)
+        && line.trim_end_matches('=').bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/');
+    (line.starts_with("H4sI") && is_base64).then_some("base64-encoded gzip data")
+}
+
+/// The first `\uXXXX` escape in a valid line that stands for a printable ASCII character other than
+/// `"` and `\`, returned with the character it stands for
+fn unnecessary_unicode_escape(line: &str) -> Option<(&str, char)> {
+    if !line.contains("\\u") || serde_json::from_str::<serde::de::IgnoredAny>(line).is_err() {
+        return None;
+    }
+
+    // Backslashes only appear inside strings in valid JSON, so every one starts an escape
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            i += 1;
+            continue;
+        }
+        if bytes.get(i + 1) == Some(&b'u') {
+            let escape = &line[i..i + 6];
+            // Halves of surrogate pairs aren't characters on their own, but they're never ASCII
+            let character = u32::from_str_radix(&escape[2..], 16).ok().and_then(char::from_u32);
+            if let Some(character) = character.filter(|c| (' '..='~').contains(c) && *c != '"' && *c != '\\') {
+                return Some((escape, character));
+            }
+            i += 6;
+        } else {
+            i += 2;
+        }
+    }
+    None
+}
+
+/// Moves the warnings into the errors if `ValidatorConfig::warnings_as_errors` is set
+pub(crate) fn promote_warnings(errors: &mut Vec<ValidationError>, warnings: &mut Vec<ValidationWarning>, config: &ValidatorConfig) {
+    if config.warnings_as_errors {
+        errors.extend(warnings.drain(..).map(ValidationWarning::into_error));
+    }
+}
+
+/// Checks the lines of a single input in order, keeping track of state that spans lines
+pub(crate) struct LineChecker {
+    /// Whether the schema header configured by `ValidatorConfig::skip_schema_header` is still to come
+    schema_header_pending: bool,
+    /// Top-level keys of the first valid line, once seen, for `ValidatorConfig::enforce_consistent_schema`
+    expected_keys: Option<BTreeSet<String>>,
+}
+
+impl LineChecker {
+    pub(crate) fn new(config: &ValidatorConfig) -> Self {
+        Self {
+            schema_header_pending: config.skip_schema_header,
+            expected_keys: None,
+        }
+    }
+
+    /// Returns `true` if this line is the schema header, i.e. the first non-empty line after any
+    /// `skip_header_lines`. Lines must be passed in order.
+    pub(crate) fn is_schema_header(&mut self, line: &str, line_number: usize, config: &ValidatorConfig) -> bool {
+        if self.schema_header_pending
+            && line_number > config.skip_header_lines
+            && !line.trim().is_empty()
+            && !is_comment(line, config)
+        {
+            self.schema_header_pending = false;
+            return true;
+        }
+        false
+    }
+
+    /// Like `check_line`, but passes the schema header through without validating it and, with
+    /// `ValidatorConfig::enforce_consistent_schema`, checks each valid line against the keys of the
+    /// first one
+    pub(crate) fn check(
+        &mut self,
+        line: &str,
+        line_number: usize,
+        file_path: &Path,
+        config: &ValidatorConfig,
+        backend: Backend,
+    ) -> Option<ValidationError> {
+        if self.is_schema_header(line, line_number, config) {
+            return None;
+        }
+        let error = check_line(line, line_number, file_path, config, backend);
+        if error.is_some() || !config.enforce_consistent_schema || is_skipped(line, line_number, config) {
+            return error;
+        }
+
+        let keys = top_level_keys(line);
+        let expected = self.expected_keys.get_or_insert_with(|| keys.clone());
+        if keys == *expected {
+            return None;
+        }
+        let message = format!(
+            "missing keys: {}, extra keys: {}",
+            format_keys(expected.difference(&keys)),
+            format_keys(keys.difference(expected)),
+        );
+        Some(line_error(line, line_number, file_path, config, ParseError::without_position(message)))
+    }
+}
+
+/// Top-level keys of a valid line. Lines that aren't objects have none.
+fn top_level_keys(line: &str) -> BTreeSet<String> {
+    match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(map)) => map.into_iter().map(|(key, _)| key).collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+/// Formats keys as a list like `['id', 'name']`
+fn format_keys<'a>(keys: impl Iterator<Item = &'a String>) -> String {
+    let keys: Vec<String> = keys.map(|key| format!("'{}'", key)).collect();
+    format!("[{}]", keys.join(", "))
+}
+
+/// Content to store in `ValidationError::line_content` for an invalid line. With
+/// `ValidatorConfig::hash_line_content` this is a hash, so the line itself is never kept.
+pub(crate) fn reported_line_content(line: &[u8], config: &ValidatorConfig) -> String {
+    if config.hash_line_content {
+        format!("sha256:{}", hex::encode(Sha256::digest(line)))
+    } else {
+        String::from_utf8_lossy(line).into_owned()
+    }
+}
+
+/// Whether a line is a `//` comment that `ValidatorConfig::allow_comment_lines` lets through
+fn is_comment(line: &str, config: &ValidatorConfig) -> bool {
+    config.allow_comment_lines && line.trim_start().starts_with("//")
+}
+
+/// Whether a line is passed through without being validated: a header line, a blank line or an
+/// allowed comment
+pub(crate) fn is_skipped(line: &str, line_number: usize, config: &ValidatorConfig) -> bool {
+    line_number <= config.skip_header_lines || line.trim().is_empty() || is_comment(line, config)
+}
+
+/// Checks a single line, returning a validation error if it is invalid
+pub(crate) fn check_line(
+    line: &str,
+    line_number: usize,
+    file_path: &Path,
+    config: &ValidatorConfig,
+    backend: Backend,
+) -> Option<ValidationError> {
+    // Header and comment lines are passed through untouched, but still count towards line numbers
+    if is_skipped(line, line_number, config) {
+        return None;
+    }
+
+    // Checked before parsing, since serde_json gives up on anything nested deeper than 128 levels
+    // with a less helpful recursion limit error
+    let depth_limit = config.max_nesting_depth.map(|max| (nesting_depth(line), max));
+    let normalized = if config.normalize_line_endings && line.contains('\r') {
+        Cow::Owned(line.replace('\r', ""))
+    } else {
+        Cow::Borrowed(line)
+    };
+
+    let error = match depth_limit {
+        Some((depth, max)) if depth > max => Some(ParseError::without_position(format!(
+            "nesting depth {} exceeds maximum {}",
+            depth, max
+        ))),
+        _ => backend.parse_error(&normalized),
+    };
+    let error = match error {
+        Some(error) => error,
+        None => ParseError::without_position(find_value_error(&normalized, config)?),
+    };
+    Some(line_error(line, line_number, file_path, config, error))
+}
+
+/// Checks that only apply to lines that parse, as enabled in `config`
+fn find_value_error(line: &str, config: &ValidatorConfig) -> Option<String> {
+    if config.strict_jsonlines {
+        let root = match line.trim_start().as_bytes().first() {
+            Some(b'{' | b'[') => None,
+            Some(b'"') => Some("string"),
+            Some(b't' | b'f') => Some("boolean"),
+            Some(b'n') => Some("null"),
+            _ => Some("number"),
+        };
+        if let Some(root) = root {
+            return Some(format!("root value is a {}, but strict JSON Lines requires an object or array", root));
+        }
+    }
+    if let Some(min_fields) = config.min_fields_per_object {
+        if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(line) {
+            if map.len() < min_fields {
+                return Some(format!("object has {} fields, minimum is {}", map.len(), min_fields));
+            }
+        }
+    }
+    if config.detect_duplicate_keys {
+        if let Some(message) = find_duplicate_key(line) {
+            return Some(message);
+        }
+    }
+    if config.check_float_precision {
+        return find_imprecise_float(line);
+    }
+    None
+}
+
+/// Builds the validation error reported for an invalid line
+pub(crate) fn line_error(
+    line: &str,
+    line_number: usize,
+    file_path: &Path,
+    config: &ValidatorConfig,
+    error: ParseError,
+) -> ValidationError {
+    let line_hash = config
+        .compute_line_hashes
+        .then(|| Sha256::digest(line.as_bytes()).into());
+
+    ValidationError {
+        file_path: file_path.to_path_buf(),
+        line_number,
+        line_content: reported_line_content(line.as_bytes(), config),
+        error: error.message,
+        line_hash,
+        column_number: error.column_number,
+        sonic_error_offset: error.sonic_error_offset,
+    }
+}
+
+/// Deepest nesting of arrays and objects in a line, ignoring brackets inside strings.
+///
+/// This is a plain byte scan rather than a walk over a parsed value, so it works on lines far too
+/// deep for a recursive parser.
+fn nesting_depth(line: &str) -> usize {
+    let mut depth: usize = 0;
+    let mut max_depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for byte in line.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    max_depth
+}
+
+/// Validates ND-JSON from any buffered reader, such as standard input, a socket or an in-memory
+/// `Cursor`, without writing it to a file first.
+///
+/// `label` is used as the `file_path` of the returned errors.
+pub fn validate_reader_serde(reader: impl BufRead, label: &str) -> Result<Vec<ValidationError>> {
+    validate_reader(reader, Path::new(label), &ValidatorConfig::default(), Backend::Serde)
+}
+
+/// Validates ND-JSON that is already in memory. Lines are split and skipped exactly as when
+/// validating a file, and `label` is used as the `file_path` of the returned errors.
+pub fn validate_str_serde(content: &str, label: &str) -> Result<Vec<ValidationError>> {
+    validate_reader_serde(content.as_bytes(), label)
+}
+
+/// Validates a single ND-JSON file and returns a list of validation errors
+pub fn validate_file_serde(file_path: &Path) -> Result<Vec<ValidationError>> {
+    let file = File::open(file_path)?;
+    validate_reader(BufReader::new(file), file_path, &ValidatorConfig::default(), Backend::Serde)
+}
+
+/// Validates a single ND-JSON file, honouring the line handling options in `config`
+pub fn validate_file_with_config_serde(file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+    let file = File::open(file_path)?;
+    validate_reader(BufReader::new(file), file_path, config, Backend::Serde)
+}
+
+/// Validates a single ND-JSON file, also returning the byte offset of the start of each valid line.
+///
+/// The offsets form an index of the file: seeking to one with `SeekFrom::Start` positions a reader
+/// at the start of that line. Blank lines are not included.
+pub fn validate_file_with_index_serde(file_path: &Path) -> Result<(Vec<ValidationError>, Vec<u64>)> {
     let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
+    let config = ValidatorConfig::default();
+    let mut checker = LineChecker::new(&config);
     let mut errors = Vec::new();
+    let mut offsets = Vec::new();
 
-    for (i, line_result) in reader.lines().enumerate() {
-        let line_number = i + 1;
-        let line = line_result?;
-        
-        if line.trim().is_empty() {
+    for line in Lines::new(BufReader::new(file), file_path) {
+        let line = line?;
+        match checker.check(&line.content, line.number, file_path, &config, Backend::Serde) {
+            Some(error) => errors.push(error),
+            None if !line.content.trim().is_empty() => offsets.push(line.byte_offset),
+            None => {}
+        }
+    }
+
+    Ok((errors, offsets))
+}
+
+/// Validates a single ND-JSON file, also returning the warnings enabled in `config`, such as
+/// `ValidatorConfig::detect_single_line_array`
+pub fn validate_file_with_warnings_serde(
+    file_path: &Path,
+    config: &ValidatorConfig,
+) -> Result<(Vec<ValidationError>, Vec<ValidationWarning>)> {
+    let mut errors = validate_file_with_config_serde(file_path, config)?;
+    let mut warnings = find_warnings(BufReader::new(File::open(file_path)?), file_path, config)?;
+    promote_warnings(&mut errors, &mut warnings, config);
+    Ok((errors, warnings))
+}
+
+/// Validates a single ND-JSON file and checks that every valid line deserializes into `T`.
+///
+/// Lines that are not valid JSON are reported as usual. Lines that are valid JSON but don't fit
+/// `T`, e.g. because a field is missing or has the wrong type, are reported with an error starting
+/// with `schema mismatch:`.
+pub fn validate_typed_serde<T: DeserializeOwned>(file_path: &Path) -> Result<Vec<ValidationError>> {
+    let file = File::open(file_path)?;
+    let config = ValidatorConfig::default();
+    let mut errors = Vec::new();
+
+    for line in Lines::new(BufReader::new(file), file_path) {
+        let line = line?;
+        if let Some(error) = check_line(&line.content, line.number, file_path, &config, Backend::Serde) {
+            errors.push(error);
             continue;
         }
-        
-        match sonic_rs::from_str::<LazyValue>(&line) {
-            Ok(_) => {}
-            Err(e) => {
-                errors.push(ValidationError {
-                    file_path: file_path.to_path_buf(),
-                    line_number,
-                    line_content: line,
-                    error: e.to_string(),
-                });
-            }
+        if is_skipped(&line.content, line.number, &config) {
+            continue;
+        }
+
+        if let Err(e) = serde_json::from_str::<T>(&line.content) {
+            let error = ParseError {
+                message: format!("schema mismatch: {}", e),
+                column_number: Some(e.column()),
+                sonic_error_offset: None,
+            };
+            errors.push(line_error(&line.content, line.number, file_path, &config, error));
         }
     }
 
     Ok(errors)
 }
 
+/// Returns `true` if the file contains no invalid lines, stopping at the first invalid one
+pub fn is_valid_serde(file_path: &Path) -> Result<bool> {
+    is_valid(file_path, Backend::Serde)
+}
+
+/// Returns `true` if the file contains no invalid lines according to sonic-rs, stopping at the first invalid one
+pub fn is_valid_sonic(file_path: &Path) -> Result<bool> {
+    is_valid(file_path, Backend::Sonic)
+}
+
+fn is_valid(file_path: &Path, backend: Backend) -> Result<bool> {
+    let file = File::open(file_path)?;
+    let config = ValidatorConfig::default();
+
+    for line in Lines::new(BufReader::new(file), file_path) {
+        let line = line?;
+        if check_line(&line.content, line.number, file_path, &config, backend).is_some() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Validates a single ND-JSON file using sonic-rs and returns a list of validation errors
+pub fn validate_file_sonic(file_path: &Path) -> Result<Vec<ValidationError>> {
+    let file = File::open(file_path)?;
+    validate_reader(BufReader::new(file), file_path, &ValidatorConfig::default(), Backend::Sonic)
+}
+
+/// Validates ND-JSON that is already in memory using sonic-rs. `label` is used as the `file_path`
+/// of the returned errors.
+pub fn validate_str_sonic(content: &str, label: &str) -> Result<Vec<ValidationError>> {
+    validate_reader_sonic(content.as_bytes(), label)
+}
+
+/// Validates ND-JSON from any buffered reader using sonic-rs. `label` is used as the `file_path`
+/// of the returned errors.
+pub fn validate_reader_sonic(reader: impl BufRead, label: &str) -> Result<Vec<ValidationError>> {
+    validate_reader(reader, Path::new(label), &ValidatorConfig::default(), Backend::Sonic)
+}
+
+/// Validates a single ND-JSON file using sonic-rs, honouring the line handling options in `config`
+pub fn validate_file_with_config_sonic(file_path: &Path, config: &ValidatorConfig) -> Result<Vec<ValidationError>> {
+    let file = File::open(file_path)?;
+    validate_reader(BufReader::new(file), file_path, config, Backend::Sonic)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +790,557 @@ mod tests {
         let errors = validate_file_serde(file_path).unwrap();
         assert_eq!(errors.len(), 8); // All lines except first and last are invalid
     }
+    
+    #[test]
+    fn test_validate_reader() {
+        let content = std::fs::read("tests/invalid2.ndjson").unwrap();
+        let serde_errors = validate_reader_serde(std::io::Cursor::new(content.clone()), "upload").unwrap();
+        let sonic_errors = validate_reader_sonic(std::io::Cursor::new(content), "upload").unwrap();
+
+        assert_eq!(serde_errors.len(), 8);
+        assert_eq!(sonic_errors.len(), 8);
+        assert!(serde_errors.iter().chain(&sonic_errors).all(|e| e.file_path == Path::new("upload")));
+        assert_eq!(
+            serde_errors.iter().map(|e| e.line_number).collect::<Vec<_>>(),
+            sonic_errors.iter().map(|e| e.line_number).collect::<Vec<_>>()
+        );
+    }
+    
+    #[test]
+    fn test_validate_str() {
+        let content = "{\"a\": 1}\n\n{\"a\": }\r\n  \n[1, 2\n{\"a\": 2}";
+        for errors in [validate_str_serde(content, "response").unwrap(), validate_str_sonic(content, "response").unwrap()] {
+            assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![3, 5]);
+            assert_eq!(errors[0].file_path, PathBuf::from("response"));
+            assert_eq!(errors[1].line_content, "[1, 2");
+        }
+
+        assert!(validate_str_serde("", "empty").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_max_errors_per_file() {
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(3),
+            ..Default::default()
+        };
+        let errors = validate_file_with_config_sonic(Path::new("tests/invalid2.ndjson"), &config).unwrap();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[2].line_number, 4);
+
+        let config = ValidatorConfig {
+            max_errors_per_file: Some(0),
+            ..Default::default()
+        };
+        assert!(validate_file_with_config_serde(Path::new("tests/invalid2.ndjson"), &config).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_fail_fast() {
+        let config = ValidatorConfig {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let file_path = Path::new("tests/invalid2.ndjson");
+        for errors in [
+            validate_file_with_config_serde(file_path, &config).unwrap(),
+            validate_file_with_config_sonic(file_path, &config).unwrap(),
+        ] {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].line_number, 2);
+        }
+    }
+    
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid_serde(Path::new("tests/valid.ndjson")).unwrap());
+        assert!(is_valid_sonic(Path::new("tests/valid.ndjson")).unwrap());
+        assert!(!is_valid_serde(Path::new("tests/invalid1.ndjson")).unwrap());
+        assert!(!is_valid_sonic(Path::new("tests/invalid2.ndjson")).unwrap());
+    }
+    
+    #[test]
+    fn test_non_finite_numbers_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("non_finite.ndjson");
+        std::fs::write(&file_path, "{\"val\": NaN}\n{\"val\": Infinity}\n{\"val\": -Infinity}\n").unwrap();
+
+        // Neither backend accepts these literals, so no separate strict number mode is needed
+
+        let serde_lines = validate_file_serde(&file_path).unwrap().iter().map(|e| e.line_number).collect::<Vec<_>>();
+        let sonic_lines = validate_file_sonic(&file_path).unwrap().iter().map(|e| e.line_number).collect::<Vec<_>>();
+        assert_eq!(serde_lines, vec![1, 2, 3]);
+        assert_eq!(sonic_lines, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_non_utf8_path_is_reported_unchanged() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join(OsStr::from_bytes(b"data-\xff.ndjson"));
+        std::fs::write(&file_path, "{\"a\": }\n").unwrap();
+
+        assert_eq!(validate_file_serde(&file_path).unwrap()[0].file_path, file_path);
+        assert_eq!(validate_file_sonic(&file_path).unwrap()[0].file_path, file_path);
+        let errors = crate::iter_file_serde(&file_path).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(errors[0].file_path, file_path);
+    }
+
+    #[test]
+    fn test_skip_header_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("with_header.ndjson");
+        std::fs::write(&file_path, "# exported 2024-01-01\n# vendor: acme\n{\"a\": 1}\n{\"a\": }\n").unwrap();
+        
+        // Without skipping, the header lines are reported as errors
+        let errors = validate_file_serde(&file_path).unwrap();
+        assert_eq!(errors.len(), 3);
+        
+        let config = ValidatorConfig {
+            skip_header_lines: 2,
+            ..Default::default()
+        };
+        for errors in [
+            validate_file_with_config_serde(&file_path, &config).unwrap(),
+            validate_file_with_config_sonic(&file_path, &config).unwrap(),
+        ] {
+            assert_eq!(errors.len(), 1);
+            // Line numbers still count the header lines
+            assert_eq!(errors[0].line_number, 4);
+        }
+    }
+    
+    #[test]
+    fn test_skip_schema_header() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("with_schema.ndjson");
+        // The schema header is not valid JSON here, to show that it is not validated at all
+        std::fs::write(&file_path, "# export\n\n{\"schema\": {\"a\": \"int\"}\n{\"a\": 1}\n{\"a\": }\n").unwrap();
+        
+        let config = ValidatorConfig {
+            skip_header_lines: 1,
+            skip_schema_header: true,
+            ..Default::default()
+        };
+        let errors = validate_file_with_config_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 5);
+        
+        // Without the option the schema header is validated like any other line
+        let config = ValidatorConfig {
+            skip_header_lines: 1,
+            ..Default::default()
+        };
+        assert_eq!(validate_file_with_config_sonic(&file_path, &config).unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn test_nesting_depth() {
+        assert_eq!(nesting_depth("1"), 0);
+        assert_eq!(nesting_depth("{\"a\": [1, {\"b\": []}]}"), 4);
+        assert_eq!(nesting_depth("{\"a\": \"[[[{{\\\"\"}"), 1);
+    }
+    
+    #[test]
+    fn test_max_nesting_depth() {
+        let deep = format!("{}{}\n", "[".repeat(1000), "]".repeat(1000));
+        let content = format!("{{\"a\": [1]}}\n{}", deep);
+        
+        let config = ValidatorConfig {
+            max_nesting_depth: Some(64),
+            ..Default::default()
+        };
+        for backend in [Backend::Serde, Backend::Sonic] {
+            let errors = validate_reader(content.as_bytes(), Path::new("deep"), &config, backend).unwrap();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].line_number, 2);
+            assert_eq!(errors[0].error, "nesting depth 1000 exceeds maximum 64");
+        }
+        
+        let config = ValidatorConfig {
+            max_nesting_depth: Some(1),
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("deep"), &config, Backend::Serde).unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].error, "nesting depth 2 exceeds maximum 1");
+    }
+    
+    #[test]
+    fn test_detect_duplicate_keys() {
+        let content = "{\"a\": 1, \"a\": 2}\n{\"a\": 1, \"b\": 2}\n";
+        
+        let errors = validate_reader(content.as_bytes(), Path::new("dupes"), &ValidatorConfig::default(), Backend::Serde).unwrap();
+        assert!(errors.is_empty());
+        
+        let config = ValidatorConfig {
+            detect_duplicate_keys: true,
+            ..Default::default()
+        };
+        for backend in [Backend::Serde, Backend::Sonic] {
+            let errors = validate_reader(content.as_bytes(), Path::new("dupes"), &config, backend).unwrap();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].line_number, 1);
+            assert_eq!(errors[0].error, "duplicate key 'a' in object");
+        }
+    }
+    
+    #[test]
+    fn test_allow_comment_lines() {
+        let content = "// generated by exporter v2\n{\"a\": 1}\n  // trailing note\n{\"a\": }\n";
+        
+        let errors = validate_reader(content.as_bytes(), Path::new("comments"), &ValidatorConfig::default(), Backend::Serde).unwrap();
+        assert_eq!(errors.len(), 3);
+        
+        let config = ValidatorConfig {
+            allow_comment_lines: true,
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("comments"), &config, Backend::Sonic).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 4);
+    }
+    
+    #[test]
+    fn test_hash_line_content() {
+        let config = ValidatorConfig {
+            hash_line_content: true,
+            ..Default::default()
+        };
+        let errors = validate_reader("{\"email\": \"a@example.com\",}\n".as_bytes(), Path::new("pii"), &config, Backend::Serde).unwrap();
+        
+        assert_eq!(errors.len(), 1);
+        assert!(!errors[0].line_content.contains("example.com"));
+        assert_eq!(
+            errors[0].line_content,
+            format!("sha256:{}", hex::encode(Sha256::digest("{\"email\": \"a@example.com\",}")))
+        );
+    }
+    
+    #[test]
+    fn test_enforce_consistent_schema() {
+        // The invalid first line doesn't define the schema, the first valid one does
+        let content = "{\"id\": }\n{\"id\": 1, \"name\": \"a\"}\n{\"name\": \"b\", \"id\": 2}\n{\"uuid\": \"c\", \"name\": \"c\"}\n";
+        
+        assert_eq!(validate_reader(content.as_bytes(), Path::new("drift"), &ValidatorConfig::default(), Backend::Serde).unwrap().len(), 1);
+        
+        let config = ValidatorConfig {
+            enforce_consistent_schema: true,
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("drift"), &config, Backend::Sonic).unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].line_number, 4);
+        assert_eq!(errors[1].error, "missing keys: ['id'], extra keys: ['uuid']");
+    }
+    
+    #[test]
+    fn test_validate_file_with_index() {
+        use std::io::{Read, Seek, SeekFrom};
+        
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("indexed.ndjson");
+        std::fs::write(&file_path, "{\"a\": 1}\r\n{\"a\": }\n\n{\"é\": 3}\n[4]").unwrap();
+        
+        let (errors, offsets) = validate_file_with_index_serde(&file_path).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(offsets, vec![0, 19, 29]);
+        
+        // Seeking to an offset lands on the start of the valid line
+        let mut file = File::open(&file_path).unwrap();
+        file.seek(SeekFrom::Start(offsets[1])).unwrap();
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line).unwrap();
+        assert_eq!(line, "{\"é\": 3}\n");
+        
+        let mut rest = String::new();
+        let mut file = File::open(&file_path).unwrap();
+        file.seek(SeekFrom::Start(offsets[2])).unwrap();
+        file.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "[4]");
+    }
+    
+    #[test]
+    fn test_check_float_precision() {
+        let content = "{\"price\": 19.99}\n{\"price\": 0.30000000000000000001}\n";
+        
+        assert!(validate_reader(content.as_bytes(), Path::new("prices"), &ValidatorConfig::default(), Backend::Serde).unwrap().is_empty());
+        
+        let config = ValidatorConfig {
+            check_float_precision: true,
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("prices"), &config, Backend::Sonic).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 2);
+    }
+    
+    #[test]
+    fn test_detect_single_line_array() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = ValidatorConfig {
+            detect_single_line_array: true,
+            ..Default::default()
+        };
+        
+        let array_path = temp_dir.path().join("array.ndjson");
+        std::fs::write(&array_path, "\n[{\"a\": 1}, {\"a\": 2}]\n").unwrap();
+        let (errors, warnings) = validate_file_with_warnings_serde(&array_path, &config).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].file_path, array_path);
+        
+        // Only reported when enabled
+        let (_, warnings) = validate_file_with_warnings_serde(&array_path, &ValidatorConfig::default()).unwrap();
+        assert!(warnings.is_empty());
+        
+        // Arrays are fine as one line among several, and so is a single object
+        for content in ["[1]\n[2]\n", "{\"a\": [1]}\n", "[1,\n"] {
+            std::fs::write(&array_path, content).unwrap();
+            let (_, warnings) = validate_file_with_warnings_serde(&array_path, &config).unwrap();
+            assert!(warnings.is_empty(), "{:?} should not warn", content);
+        }
+    }
+    
+    #[test]
+    fn test_detect_compressed_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("compressed.ndjson");
+        std::fs::write(
+            &file_path,
+            b"{\"a\": 1}\nH4sIAAAAAAAAA6tWyk6tVLJSUCpLzClNVaoFAGl3pKcQAAAA\n\x1f\x8b\x08\x00\n\"H4sI\"\n",
+        )
+        .unwrap();
+        let config = ValidatorConfig {
+            detect_compressed_lines: true,
+            encoding_fallback: true,
+            ..Default::default()
+        };
+        
+        let (errors, warnings) = validate_file_with_warnings_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(warnings.iter().map(|w| w.line_number).collect::<Vec<_>>(), vec![Some(2), Some(3)]);
+        assert_eq!(
+            warnings[0].message,
+            "line 2 looks like base64-encoded gzip data rather than JSON; decompress it before validating"
+        );
+        assert!(warnings[1].message.contains("looks like gzip data"));
+    }
+    
+    #[test]
+    fn test_strict_unicode() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("escaped.ndjson");
+        std::fs::write(
+            &file_path,
+            concat!(
+                "{\"name\": \"caf\\u00e9\", \"quote\": \"\\u0022\", \"tab\": \"\\u0009\"}\n",
+                "{\"emoji\": \"\\ud83d\\ude00\", \"name\": \"\\u0041\"}\n",
+                "{\"name\": \"\\\\u0041\"}\n",
+                "{\"name\": \"\\u0041lice\"}\n",
+                "{\"name\": \"\\u0041lice\"\n",
+            ),
+        )
+        .unwrap();
+        let config = ValidatorConfig {
+            strict_unicode: true,
+            ..Default::default()
+        };
+
+        let (errors, warnings) = validate_file_with_warnings_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(warnings.iter().map(|w| w.line_number).collect::<Vec<_>>(), vec![Some(2), Some(4)]);
+        assert_eq!(warnings[1].message, "line 4 escapes 'A' as \\u0041, which doesn't need escaping");
+    }
+
+    #[test]
+    fn test_check_newline_consistency() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("mixed.ndjson");
+        let config = ValidatorConfig {
+            check_newline_consistency: true,
+            ..Default::default()
+        };
+        
+        std::fs::write(&file_path, "{\"a\": 1}\r\n{\"a\": 2}\r\n{\"a\": 3}").unwrap();
+        let (_, warnings) = validate_file_with_warnings_serde(&file_path, &config).unwrap();
+        assert!(warnings.is_empty());
+        
+        std::fs::write(&file_path, "{\"a\": 1}\n{\"a\": 2}\r\n{\"a\": 3}\r\n").unwrap();
+        let (errors, warnings) = validate_file_with_warnings_serde(&file_path, &config).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "line 2 ends with \\r\\n (at byte 17), but the first line ends with \\n; the file mixes newline styles"
+        );
+    }
+    
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_validate_typed() {
+        #[derive(serde::Deserialize)]
+        #[allow(dead_code)]
+        struct User {
+            id: u64,
+            name: String,
+        }
+        
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("users.ndjson");
+        std::fs::write(
+            &file_path,
+            "{\"id\": 1, \"name\": \"a\", \"extra\": true}\n{\"id\": 2}\n\n{\"id\": \"3\", \"name\": \"c\"}\n{\"id\": }\n",
+        )
+        .unwrap();
+        
+        let errors = validate_typed_serde::<User>(&file_path).unwrap();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].line_number, 2);
+        assert!(errors[0].error.starts_with("schema mismatch: missing field `name`"));
+        assert_eq!(errors[1].line_number, 4);
+        assert!(errors[1].error.starts_with("schema mismatch: invalid type: string \"3\""));
+        assert_eq!(errors[2].line_number, 5);
+        assert!(!errors[2].error.starts_with("schema mismatch"));
+    }
+    
+    #[test]
+    fn test_line_range() {
+        let content = "{\"a\": }\n{\"a\": 2}\n{\"a\": }\n{\"a\": }\n{\"a\": 5}\n{\"a\": }\n";
+        let config = ValidatorConfig {
+            line_range: Some(2..5),
+            ..Default::default()
+        };
+        
+        let scan = scan_reader(content.as_bytes(), Path::new("range"), &config, Backend::Serde, None).unwrap();
+        assert_eq!(scan.errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(scan.lines_scanned, 3);
+    }
+    
+    #[test]
+    fn test_strict_jsonlines() {
+        let content = "{\"a\": 1}\n [1]\nnull\ntrue\n-1\n\"text\"\n";
+        
+        assert!(validate_reader(content.as_bytes(), Path::new("strict"), &ValidatorConfig::default(), Backend::Serde).unwrap().is_empty());
+        
+        let config = ValidatorConfig {
+            strict_jsonlines: true,
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("strict"), &config, Backend::Sonic).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+        assert_eq!(errors[0].error, "root value is a null, but strict JSON Lines requires an object or array");
+    }
+    
+    #[test]
+    fn test_min_fields_per_object() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("truncated.ndjson");
+        std::fs::write(&file_path, "{\"a\": 1, \"b\": 2, \"c\": 3}\n{\"a\": 1, \"b\": 2}\n[1]\n\"text\"\n").unwrap();
+        let config = ValidatorConfig {
+            min_fields_per_object: Some(3),
+            ..Default::default()
+        };
+
+        let errors = validate_file_with_config_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 2);
+        assert_eq!(errors[0].error, "object has 2 fields, minimum is 3");
+    }
+
+    #[test]
+    fn test_line_hashes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("repeated.ndjson");
+        std::fs::write(&file_path, "{\"a\": }\n{\"b\": 2}\n{\"a\": }\n[1,\n").unwrap();
+        
+        // Hashes are only computed on request
+        let errors = validate_file_serde(&file_path).unwrap();
+        assert!(errors.iter().all(|e| e.line_hash.is_none()));
+        
+        let config = ValidatorConfig {
+            compute_line_hashes: true,
+            ..Default::default()
+        };
+        let errors = validate_file_with_config_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.len(), 3);
+        
+        // The same bad line hashes identically, a different one does not
+        assert!(errors[0].line_hash.is_some());
+        assert_eq!(errors[0].line_hash, errors[1].line_hash);
+        assert_ne!(errors[0].line_hash, errors[2].line_hash);
+    }
+    
+    #[test]
+    fn test_normalize_line_endings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("stray_cr.ndjson");
+        // A carriage return inside a string is a raw control character, which JSON forbids
+        std::fs::write(&file_path, "{\"a\": \"x\ry\"}\r\n{\"b\": 2}\n").unwrap();
+        
+        assert_eq!(validate_file_serde(&file_path).unwrap().len(), 1);
+        
+        let config = ValidatorConfig {
+            normalize_line_endings: true,
+            ..Default::default()
+        };
+        assert!(validate_file_with_config_serde(&file_path, &config).unwrap().is_empty());
+        assert!(validate_file_with_config_sonic(&file_path, &config).unwrap().is_empty());
+
+        // The value checks see the same normalized line as the parser
+        let config = ValidatorConfig {
+            normalize_line_endings: true,
+            min_fields_per_object: Some(2),
+            ..Default::default()
+        };
+        let errors = validate_file_with_config_serde(&file_path, &config).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(errors[0].error, "object has 1 fields, minimum is 2");
+    }
+    
+    #[test]
+    fn test_first_line_only_after_schema_header() {
+        let content = "// comment\n\n{\"columns\": [\"a\"]}\n{\"a\": }\n{\"a\": }\n";
+        let config = ValidatorConfig {
+            first_line_only: true,
+            skip_schema_header: true,
+            allow_comment_lines: true,
+            ..Default::default()
+        };
+        let errors = validate_reader(content.as_bytes(), Path::new("test"), &config, Backend::Serde).unwrap();
+        assert_eq!(errors.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let content = "{\"name\": \"Zoë\", \"age\": }\n";
+        
+        let errors = validate_reader(content.as_bytes(), Path::new("test"), &ValidatorConfig::default(), Backend::Serde).unwrap();
+        assert!(errors[0].column_number.is_some());
+        assert_eq!(errors[0].sonic_error_offset, None);
+        
+        let errors = validate_reader(content.as_bytes(), Path::new("test"), &ValidatorConfig::default(), Backend::Sonic).unwrap();
+        let offset = errors[0].sonic_error_offset.unwrap();
+        // The offset points at the closing brace where a value was expected
+        assert_eq!(&content[offset..offset + 1], "}");
+    }
+    
+    #[test]
+    fn test_non_utf8_file_reports_encoding_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("latin1.ndjson");
+        // "é" encoded as Latin-1 on the second line
+        std::fs::write(&file_path, b"{\"a\": 1}\n{\"name\": \"Ren\xe9\"}\n").unwrap();
+        
+        match validate_file_serde(&file_path) {
+            Err(NdJsonError::UnsupportedEncoding { path, byte_offset }) => {
+                assert_eq!(path, file_path);
+                assert_eq!(byte_offset, 22);
+            }
+            other => panic!("expected an encoding error, got {:?}", other),
+        }
+    }
 }
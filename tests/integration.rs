@@ -1,8 +1,10 @@
 use std::fs;
-use std::path::Path;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use tempfile::tempdir;
 
-use ndjson_validator::{validate_file_serde, process_file_serde, ValidatorConfig};
+use ndjson_validator::{validate_file_serde, validate_reader_serde, process_file_serde, OutputFormat, ValidatorConfig, STDIN_LABEL};
 
 #[test]
 fn test_integration_valid_ndjson() {
@@ -32,10 +34,10 @@ fn test_integration_cleaning_ndjson() {
     let output_dir = temp_dir.path();
     
     let file_path = Path::new("tests/invalid1.ndjson");
-    let config = ValidatorConfig {
-        clean_files: true,
-        output_dir: Some(output_dir.to_path_buf()),
-    };
+    let config = ValidatorConfig::builder()
+        .clean_files(true)
+        .output_dir(output_dir.to_path_buf())
+        .build();
     
     let errors = process_file_serde(file_path, &config).unwrap();
     assert_eq!(errors.len(), 1);
@@ -52,3 +54,173 @@ fn test_integration_cleaning_ndjson() {
     assert!(content.contains("Bob"));
     assert!(content.contains("Charlie"));
 }
+
+#[test]
+fn test_integration_validate_pipe_cleans_to_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ndjson-validator"))
+        .args(["validate-pipe", "--clean"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    
+    let input = fs::read("tests/invalid1.ndjson").unwrap();
+    child.stdin.take().unwrap().write_all(&input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    
+    // Only the valid lines end up on stdout, diagnostics go to stderr
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(!stdout.contains("Alice"));
+    
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("<stdin>"));
+}
+
+#[test]
+fn test_integration_validate_reader_from_child_stdout() {
+    // A pipe behaves like standard input: it can only be read once, front to back
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ndjson-validator"))
+        .args(["validate-pipe", "--clean"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"{\"a\": 1}\n{\"a\": 2}\n").unwrap();
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let errors = validate_reader_serde(stdout, STDIN_LABEL).unwrap();
+    assert!(child.wait().unwrap().success());
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_integration_validate_pipe_reports_errors() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ndjson-validator"))
+        .arg("validate-pipe")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(&fs::read("tests/invalid2.ndjson").unwrap()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    // The report goes to stderr so that stdout only ever carries cleaned lines
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Found 8 errors in input"));
+    assert!(stderr.contains("File: <stdin>"));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_integration_compat_aliases() {
+    use ndjson_validator::compat;
+
+    let files = vec![PathBuf::from("tests/invalid1.ndjson"), PathBuf::from("tests/invalid2.ndjson")];
+    let config = ValidatorConfig::default();
+
+    assert_eq!(compat::validate_file(&files[1]).unwrap().len(), 8);
+    assert_eq!(compat::validate_files(&files, &config).unwrap().len(), 9);
+    assert_eq!(compat::validate_files_with_summary(&files, &config).unwrap().summary.total_errors, 9);
+}
+
+#[test]
+fn test_integration_config_struct_literal_still_compiles() {
+    // Kept for backward compatibility alongside `ValidatorConfig::builder`
+    let literal = ValidatorConfig {
+        clean_files: true,
+        threads: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(literal, ValidatorConfig::builder().clean_files(true).threads(2).build());
+}
+
+#[test]
+fn test_integration_builder_covers_added_options() {
+    let built = ValidatorConfig::builder()
+        .max_errors_per_file(5)
+        .fail_fast(true)
+        .line_range(10..20)
+        .output_format(OutputFormat::Json)
+        .sort_by_field("id".to_string())
+        .build();
+    let literal = ValidatorConfig {
+        max_errors_per_file: Some(5),
+        fail_fast: true,
+        line_range: Some(10..20),
+        output_format: OutputFormat::Json,
+        sort_by_field: Some("id".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(built, literal);
+}
+
+#[test]
+fn test_integration_threads() {
+    let files = vec![PathBuf::from("tests/invalid1.ndjson"), PathBuf::from("tests/invalid2.ndjson")];
+    let config = ValidatorConfig::builder().threads(1).build();
+    assert_eq!(ndjson_validator::validate_files_serde(&files, &config).unwrap().len(), 9);
+}
+
+fn run_cli(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_ndjson-validator")).args(args).output().unwrap()
+}
+
+#[test]
+fn test_integration_format_json() {
+    for subcommand in ["validate-file", "validate-files"] {
+        let output = run_cli(&[subcommand, "tests/invalid1.ndjson", "--format", "json"]);
+        assert!(output.status.success());
+
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["summary"]["total_files"], 1);
+        assert_eq!(report["summary"]["total_errors"], 1);
+        assert_eq!(report["errors"][0]["file_path"], "tests/invalid1.ndjson");
+        assert_eq!(report["errors"][0]["line_number"], 1);
+        assert_eq!(report["errors"][0]["error"], "expected value at line 1 column 25");
+        assert_eq!(report["errors"][0]["column_number"], 25);
+        assert_eq!(report["errors"][0]["line_hash"], serde_json::Value::Null);
+    }
+}
+
+#[test]
+fn test_integration_human_format_uses_the_report() {
+    let output = run_cli(&["validate-files", "tests/invalid1.ndjson"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Total errors found: 1"));
+    assert!(stdout.contains("tests/invalid1.ndjson:1: expected value at line 1 column 25"));
+    assert!(stdout.contains("Found 1 errors in 1 files"));
+}
+
+#[test]
+fn test_integration_format_ndjson_and_csv() {
+    let output = run_cli(&["validate-files", "tests/invalid2.ndjson", "-f", "ndjson"]);
+    let errors = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 8);
+    assert_eq!(errors[0]["line_number"], 2);
+
+    let output = run_cli(&["validate-files", "tests/invalid2.ndjson", "--output-format", "csv"]);
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(csv.lines().next(), Some("file_path,line_number,line_content,error"));
+    assert_eq!(csv.lines().count(), 9);
+
+    // `text` is another name for the human format
+    let output = run_cli(&["validate-file", "tests/valid.ndjson", "-f", "text"]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("File is valid"));
+}
+
+#[test]
+fn test_integration_first_line_only_conflicts_with_clean() {
+    let output_dir = tempdir().unwrap();
+    let output_dir = output_dir.path().to_str().unwrap();
+    let output = run_cli(&["validate-dir", "tests", "--first-line-only", "--clean", "-o", output_dir]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("cannot be used with"));
+}
@@ -0,0 +1,129 @@
+//! Edge cases of the JSON Lines format (https://jsonlines.org), run through both backends.
+//!
+//! Each line must be a valid JSON value (RFC 8259) encoded as UTF-8. Where serde_json and sonic-rs
+//! disagree, the test asserts each backend's actual behavior and notes which one the spec sides with.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use tempfile::{tempdir, TempDir};
+
+use ndjson_validator::{validate_file_serde, validate_file_sonic};
+
+/// Writes `content` to a fixture file in `dir`
+fn fixture(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+/// Numbers of the invalid lines found by serde_json and sonic-rs
+fn invalid_lines(path: &Path) -> (Vec<usize>, Vec<usize>) {
+    let line_numbers = |errors: Vec<ndjson_validator::ValidationError>| errors.iter().map(|e| e.line_number).collect();
+    (
+        line_numbers(validate_file_serde(path).unwrap()),
+        line_numbers(validate_file_sonic(path).unwrap()),
+    )
+}
+
+/// Asserts that both backends accept every line of `content`
+fn assert_both_valid(name: &str, content: &str) {
+    let dir = tempdir().unwrap();
+    let path = fixture(&dir, name, content);
+    assert_eq!(invalid_lines(&path), (vec![], vec![]), "{} should be valid", name);
+}
+
+#[test]
+fn test_empty_file() {
+    assert_both_valid("empty.jsonl", "");
+}
+
+#[test]
+fn test_single_line_without_newline() {
+    // The newline after the last line is optional
+    assert_both_valid("no_newline.jsonl", "{\"a\": 1}");
+}
+
+#[test]
+fn test_scalar_and_array_values() {
+    // Any JSON value is allowed on a line, not only objects
+    assert_both_valid("values.jsonl", "1\n\"text\"\nnull\ntrue\n[1, [2, 3], {\"a\": []}]\n[]\n");
+}
+
+#[test]
+fn test_unicode_escapes() {
+    assert_both_valid(
+        "unicode.jsonl",
+        "{\"a\": \"\\u00e9\\u4e2d\\u0000\"}\n{\"a\": \"é中\"}\n{\"\\u00e9\": \"key escapes\"}\n",
+    );
+}
+
+#[test]
+fn test_surrogate_pairs() {
+    // U+1F600 written as a UTF-16 surrogate pair
+    assert_both_valid("surrogate_pair.jsonl", "{\"a\": \"\\ud83d\\ude00\"}\n");
+}
+
+#[test]
+fn test_lone_surrogates() {
+    let dir = tempdir().unwrap();
+    let path = fixture(&dir, "lone_surrogates.jsonl", "{\"a\": \"\\ud800\"}\n{\"a\": \"\\udc00\"}\n");
+
+    // The backends disagree. The RFC 8259 grammar allows any `\uXXXX` escape, so a lone surrogate
+    // is valid JSON (section 8.2 only warns that such strings behave unpredictably), and sonic-rs
+    // is correct. serde_json rejects it because it cannot be decoded to a Rust string.
+    let (serde, sonic) = invalid_lines(&path);
+    assert_eq!(serde, vec![1, 2]);
+    assert_eq!(sonic, Vec::<usize>::new());
+}
+
+#[test]
+fn test_large_numbers() {
+    // Integers beyond 64 bits are fine for both, falling back to a float
+    assert_both_valid("large_integers.jsonl", "{\"a\": 123456789012345678901234567890}\n{\"a\": -1e-400}\n");
+
+    let dir = tempdir().unwrap();
+    let path = fixture(&dir, "huge_exponent.jsonl", "{\"a\": 1e400}\n");
+
+    // The backends disagree. The grammar puts no bound on numbers and section 6 leaves the
+    // accepted range to the implementation, so the line is valid JSON and sonic-rs is correct.
+    // serde_json rejects numbers that overflow an f64.
+    let (serde, sonic) = invalid_lines(&path);
+    assert_eq!(serde, vec![1]);
+    assert_eq!(sonic, Vec::<usize>::new());
+}
+
+#[test]
+fn test_nested_values() {
+    let nested_objects = format!("{}1{}\n", "{\"a\": ".repeat(64), "}".repeat(64));
+    let nested_arrays = format!("{}{}\n", "[".repeat(64), "]".repeat(64));
+    assert_both_valid("nested.jsonl", &format!("{}{}", nested_objects, nested_arrays));
+}
+
+#[test]
+fn test_deeply_nested_values() {
+    let dir = tempdir().unwrap();
+    let path = fixture(&dir, "deep.jsonl", &format!("{}{}\n", "[".repeat(200), "]".repeat(200)));
+
+    // sonic-rs recurses once per level, which needs more stack than a test thread has in debug builds
+    let (serde, sonic) = thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || invalid_lines(&path))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // The backends disagree. RFC 8259 section 9 lets implementations limit nesting depth, but the
+    // line is valid JSON, so sonic-rs is correct. serde_json stops at a depth of 128.
+    assert_eq!(serde, vec![1]);
+    assert_eq!(sonic, Vec::<usize>::new());
+}
+
+#[test]
+fn test_byte_order_mark() {
+    // JSON Lines is UTF-8 without a byte order mark, so both backends are right to reject it
+    let dir = tempdir().unwrap();
+    let path = fixture(&dir, "bom.jsonl", "\u{feff}{\"a\": 1}\n");
+    assert_eq!(invalid_lines(&path), (vec![1], vec![1]));
+}
@@ -0,0 +1,61 @@
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use http_body_util::BodyExt;
+use ndjson_validator::server::router;
+use tower::ServiceExt;
+
+async fn post(uri: &str, body: &'static [u8]) -> (StatusCode, Option<String>, Vec<u8>) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = response.into_body().collect().await.unwrap().to_bytes().to_vec();
+
+    (status, content_type, body)
+}
+
+#[tokio::test]
+async fn test_validate_endpoint() {
+    let (status, _, body) = post("/validate", b"{\"a\": 1}\n{\"a\": }\n\n[1, 2]\n").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(response["valid"], false);
+    assert_eq!(response["total_errors"], 1);
+    assert_eq!(response["errors"][0]["line_number"], 2);
+    assert_eq!(response["errors"][0]["line_content"], "{\"a\": }");
+}
+
+#[tokio::test]
+async fn test_validate_endpoint_valid_body() {
+    let (status, _, body) = post("/validate", b"{\"a\": 1}\n").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(response["valid"], true);
+    assert_eq!(response["errors"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_clean_endpoint() {
+    let (status, content_type, body) = post("/clean", b"{\"a\": 1}\r\n{\"a\": }\n\n{\"b\": 2}").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/x-ndjson"));
+    assert_eq!(body, b"{\"a\": 1}\n{\"b\": 2}\n");
+}
+
+#[tokio::test]
+async fn test_non_utf8_body_is_rejected() {
+    let (status, _, body) = post("/validate", b"{\"name\": \"Ren\xe9\"}\n").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(response["error"].as_str().unwrap().contains("not valid UTF-8"));
+}